@@ -0,0 +1,9 @@
+//! Shared library build of the `axis-ecies` bindings.
+//!
+//! The bindings live in [`axis_ecies::ffi`] and [`axis_ecies::wasm`]. This crate only re-exports them as a
+//! `cdylib`, enabled by the feature of the same name, so the library itself builds as an `rlib` alone.
+
+#[cfg(feature = "ffi")]
+pub use axis_ecies::ffi::*;
+#[cfg(feature = "wasm")]
+pub use axis_ecies::wasm::*;
@@ -0,0 +1,134 @@
+//! C bindings for calling the library in-process, for example from a Foundry FFI harness, enabled by the `ffi` feature.
+//!
+//! Inputs are NUL-terminated 0x-prefixed hex strings. Returned strings are allocated by the library and must be
+//! released with [`axis_ecies_free_string`].
+//!
+//! The shared library is built from the `axis-ecies-bindings` crate, e.g. with `pnpm run build:ecies-ffi`.
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use crate::strings;
+
+/// Reads a C string argument as UTF-8, returning `None` for a null pointer or invalid UTF-8.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string that stays valid for the returned lifetime.
+unsafe fn read_str<'a>(value: *const c_char) -> Option<&'a str> {
+    if value.is_null() {
+        return None;
+    }
+    CStr::from_ptr(value).to_str().ok()
+}
+
+/// Encrypts a message to the recipient public key using the bid private key and the salt.
+///
+/// Returns the same hex string as `ecies-cli encrypt` (the ciphertext followed by the bid public key coordinates),
+/// or null if any input is null, not valid hex, or rejected by the library.
+///
+/// # Safety
+///
+/// Each argument must be null or point to a NUL-terminated string. The returned pointer, if not null, must be
+/// released with [`axis_ecies_free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn axis_ecies_encrypt(
+    message: *const c_char,
+    public_key_x: *const c_char,
+    public_key_y: *const c_char,
+    bid_private_key: *const c_char,
+    salt: *const c_char,
+) -> *mut c_char {
+    let (Some(message), Some(public_key_x), Some(public_key_y), Some(bid_private_key), Some(salt)) = (
+        read_str(message),
+        read_str(public_key_x),
+        read_str(public_key_y),
+        read_str(bid_private_key),
+        read_str(salt),
+    ) else {
+        return ptr::null_mut();
+    };
+
+    match strings::encrypt(message, public_key_x, public_key_y, bid_private_key, salt) {
+        Ok(output) => CString::new(output)
+            .map(CString::into_raw)
+            .unwrap_or(ptr::null_mut()),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Releases a string returned by this library.
+///
+/// # Safety
+///
+/// `value` must be null or a pointer returned by this library that has not already been released.
+#[no_mangle]
+pub unsafe extern "C" fn axis_ecies_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_matches_contract_vector() {
+        let args = [
+            "0x01",
+            "0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+            "0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+            "0x03",
+            "0x01",
+        ]
+        .map(|arg| CString::new(arg).unwrap());
+
+        unsafe {
+            let output = axis_ecies_encrypt(
+                args[0].as_ptr(),
+                args[1].as_ptr(),
+                args[2].as_ptr(),
+                args[3].as_ptr(),
+                args[4].as_ptr(),
+            );
+            assert!(!output.is_null());
+            assert!(CStr::from_ptr(output)
+                .to_str()
+                .unwrap()
+                .starts_with("0xf96d7675ae04b89c9b5a9b0613d3530bb939186d05959efba9b3249a461abbc4"));
+            axis_ecies_free_string(output);
+
+            let invalid = CString::new("not hex").unwrap();
+            assert!(axis_ecies_encrypt(
+                invalid.as_ptr(),
+                args[1].as_ptr(),
+                args[2].as_ptr(),
+                args[3].as_ptr(),
+                args[4].as_ptr(),
+            )
+            .is_null());
+        }
+    }
+
+    #[test]
+    fn encrypt_returns_null_for_non_ascii_or_signed_hex() {
+        let args = ["0x01", "0x01", "0x02", "0x03", "0x01"].map(|arg| CString::new(arg).unwrap());
+
+        for invalid in ["0x\u{e9}0", "0x+f"] {
+            let invalid = CString::new(invalid).unwrap();
+            unsafe {
+                assert!(axis_ecies_encrypt(
+                    invalid.as_ptr(),
+                    args[1].as_ptr(),
+                    args[2].as_ptr(),
+                    args[3].as_ptr(),
+                    args[4].as_ptr(),
+                )
+                .is_null());
+            }
+        }
+    }
+}
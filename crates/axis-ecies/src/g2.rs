@@ -0,0 +1,127 @@
+//! ECIES with bn254 G2 public keys, for auction modules that use pairing-friendly auctioneer keys.
+//!
+//! The scheme is the same as for G1, except that the shared secret is the x coordinate of a G2 point.
+//! That coordinate is an `Fq2` element, serialized as 64 bytes in the order used by the EVM pairing
+//! precompile (EIP-197): the imaginary part `c1` followed by the real part `c0`.
+
+use ark_bn254::{Fq2, Fr as ScalarField, G2Affine as G2};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Zero;
+use tracing::{debug, debug_span};
+
+use crate::{apply_keystream, to_bytes32, Error, Hex};
+
+/// An encrypted message along with the G2 bid public key the recipient needs to decrypt it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedBid {
+    /// The encrypted message, which has the same length as the plaintext.
+    pub ciphertext: Vec<u8>,
+    /// The public key corresponding to the bid private key used to encrypt the message.
+    pub bid_public_key: G2,
+}
+
+/// Serializes an `Fq2` element as 64 bytes: `c1 || c0`, each 32 big-endian bytes.
+pub fn to_bytes64(value: Fq2) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    output[..32].copy_from_slice(&to_bytes32(value.c1));
+    output[32..].copy_from_slice(&to_bytes32(value.c0));
+    output
+}
+
+/// Constructs a G2 point from its coordinates.
+///
+/// Errors if the point is not on the curve or not in the prime-order subgroup.
+/// Unlike G1, the bn254 G2 curve has a large cofactor, so the subgroup check matters.
+pub fn point_from_coordinates(x: Fq2, y: Fq2) -> Result<G2, Error> {
+    let point = G2::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::NotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+/// Calculates the G2 public key corresponding to a private key.
+pub fn derive_public_key(private_key: ScalarField) -> Result<G2, Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+
+    Ok((G2::generator() * private_key).into_affine())
+}
+
+/// Derives the shared secret from a G2 public key and a private key.
+///
+/// Returns the x coordinate of the shared secret point serialized with [`to_bytes64`].
+/// Errors if the shared secret point is the point at infinity.
+pub fn derive_shared_secret(public_key: G2, private_key: ScalarField) -> Result<[u8; 64], Error> {
+    let _span = debug_span!("derive_shared_secret", group = "g2").entered();
+    let shared_point = (public_key * private_key).into_affine();
+    if shared_point.is_zero() {
+        return Err(Error::IdentitySharedSecret);
+    }
+    let shared_secret = to_bytes64(shared_point.x);
+    debug!(shared_secret_x = %Hex(&shared_secret));
+    Ok(shared_secret)
+}
+
+/// Encrypts a message to the recipient G2 public key using the bid private key and the salt.
+pub fn encrypt(
+    message: &[u8],
+    public_key: G2,
+    bid_private_key: ScalarField,
+    salt: &[u8; 32],
+) -> Result<EncryptedBid, Error> {
+    // Calculate the bid public key using the bid private key
+    let bid_public_key = derive_public_key(bid_private_key)?;
+
+    // Calculate the shared secret from the recipient public key and the bid private key
+    let shared_secret = derive_shared_secret(public_key, bid_private_key)?;
+
+    Ok(EncryptedBid {
+        ciphertext: apply_keystream(message, &shared_secret, salt, &[]),
+        bid_public_key,
+    })
+}
+
+/// Decrypts a ciphertext using the G2 bid public key, the recipient private key, and the salt.
+pub fn decrypt(
+    ciphertext: &[u8],
+    bid_public_key: G2,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+) -> Result<Vec<u8>, Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+
+    // Calculate the shared secret from the bid public key and the recipient private key
+    let shared_secret = derive_shared_secret(bid_public_key, private_key)?;
+
+    Ok(apply_keystream(ciphertext, &shared_secret, salt, &[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_then_decrypt_recovers_message() {
+        let private_key = ScalarField::from(2u64);
+        let public_key = derive_public_key(private_key).unwrap();
+        let salt = [7u8; 32];
+        let message = (0u8..40).collect::<Vec<u8>>();
+
+        let encrypted = encrypt(&message, public_key, ScalarField::from(3u64), &salt).unwrap();
+        let decrypted = decrypt(
+            &encrypted.ciphertext,
+            encrypted.bid_public_key,
+            private_key,
+            &salt,
+        )
+        .unwrap();
+        assert_eq!(decrypted, message);
+    }
+}
@@ -0,0 +1,307 @@
+//! ECIES with an AES-256-GCM symmetric layer, for off-chain payloads that need authentication.
+//!
+//! The key is the first symmetric key of the XOR scheme, the `keccak256` of block 0's
+//! [`symmetric_key_preimage`](crate::symmetric_key_preimage), and the 96-bit nonce is the first 12 bytes of
+//! `keccak256(salt)`. The ciphertext is followed by the 16-byte GCM tag. The contract only implements the
+//! XOR scheme, so these ciphertexts are off-chain only.
+//!
+//! [`encrypt_stream`] and [`decrypt_stream`] handle inputs too large to hold in memory with the STREAM
+//! construction: the input is split into [`STREAM_CHUNK_LENGTH`]-byte chunks, each sealed with its own tag
+//! under the same key. The nonce of each chunk is a 7-byte prefix, the first 7 bytes of
+//! `keccak256(salt || "stream")`, then the chunk index as a big-endian u32 and a flag byte that is 1 for the
+//! last chunk only, so chunks cannot be reordered, dropped, or truncated without failing a tag check.
+//!
+//! # Nonce reuse
+//!
+//! Both the key and the nonce are derived from the bid private key and the salt, so a (bid private key, salt)
+//! pair must never encrypt more than one message. Reusing one reuses a GCM nonce under the same key, which
+//! reveals the XOR of the two messages and lets anyone forge tags. Use a fresh random salt, or a fresh bid
+//! private key, for every message.
+
+use aes_gcm::{
+    aead::{
+        stream::{DecryptorBE32, EncryptorBE32},
+        Aead,
+    },
+    Aes256Gcm, Key, KeyInit, Nonce,
+};
+use ark_bn254::{Fr as ScalarField, G1Affine as G1};
+use ark_ff::Zero;
+use std::io::{Read, Write};
+
+use crate::{
+    derive_public_key, derive_shared_secret, derive_symmetric_key, keccak256, EncryptedBid, Error,
+};
+
+/// Length of the GCM tag appended to each ciphertext.
+pub const TAG_LENGTH: usize = 16;
+
+/// Length of each plaintext chunk in the streaming format; only the last chunk may be shorter.
+///
+/// Each chunk of ciphertext is [`TAG_LENGTH`] bytes longer than its plaintext.
+pub const STREAM_CHUNK_LENGTH: usize = 64 * 1024;
+
+/// Builds the cipher and nonce for a shared secret, salt, and domain.
+fn cipher(shared_secret: &[u8; 32], salt: &[u8; 32], domain: &[u8]) -> (Aes256Gcm, [u8; 12]) {
+    let key = derive_symmetric_key(shared_secret, salt, domain, 0);
+    let nonce = keccak256(salt)[..12].try_into().unwrap();
+    (Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)), nonce)
+}
+
+/// Encrypts a message to the recipient public key using the bid private key and the salt.
+///
+/// The ciphertext is [`TAG_LENGTH`] bytes longer than the message. The bid private key and salt must not
+/// be reused for another message; see [Nonce reuse](self#nonce-reuse).
+pub fn encrypt(
+    message: &[u8],
+    public_key: G1,
+    bid_private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<EncryptedBid, Error> {
+    // Calculate the bid public key and the shared secret as the XOR scheme does
+    let bid_public_key = derive_public_key(bid_private_key)?;
+    let shared_secret = derive_shared_secret(public_key, bid_private_key)?;
+
+    let (cipher, nonce) = cipher(&shared_secret, salt, domain);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), message)
+        .expect("message is within the AES-GCM length limit");
+
+    Ok(EncryptedBid {
+        ciphertext,
+        bid_public_key,
+    })
+}
+
+/// Verifies the tag of a ciphertext produced by [`encrypt`] and decrypts it.
+///
+/// Errors with [`Error::InvalidTag`] if the tag does not match or the ciphertext is too short to hold one.
+pub fn decrypt(
+    ciphertext: &[u8],
+    bid_public_key: G1,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+
+    // Calculate the shared secret from the bid public key and the recipient private key
+    let shared_secret = derive_shared_secret(bid_public_key, private_key)?;
+
+    let (cipher, nonce) = cipher(&shared_secret, salt, domain);
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext)
+        .map_err(|_| Error::InvalidTag)
+}
+
+/// Builds the cipher and the 7-byte STREAM nonce prefix for a shared secret, salt, and domain.
+fn stream_cipher(shared_secret: &[u8; 32], salt: &[u8; 32], domain: &[u8]) -> (Aes256Gcm, [u8; 7]) {
+    let (cipher, _) = cipher(shared_secret, salt, domain);
+    let prefix = keccak256(&[salt.as_slice(), b"stream"].concat())[..7]
+        .try_into()
+        .unwrap();
+    (cipher, prefix)
+}
+
+/// Reads up to `buffer.len()` bytes, stopping early only at the end of the input.
+fn read_chunk<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<usize, Error> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(Error::Io(err.kind())),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypts everything read from `reader` to the recipient public key, writing the ciphertext to `writer`
+/// one chunk at a time.
+///
+/// Returns the bid public key, which the decryptor needs along with the salt and domain. As with [`encrypt`],
+/// the bid private key and salt must not be reused for another message.
+pub fn encrypt_stream<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    public_key: G1,
+    bid_private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<G1, Error> {
+    let bid_public_key = derive_public_key(bid_private_key)?;
+    let shared_secret = derive_shared_secret(public_key, bid_private_key)?;
+
+    let (cipher, prefix) = stream_cipher(&shared_secret, salt, domain);
+    let mut encryptor = EncryptorBE32::from_aead(cipher, prefix.as_slice().into());
+
+    // Read one chunk ahead, since the last chunk is sealed differently
+    let mut chunk = vec![0u8; STREAM_CHUNK_LENGTH];
+    let mut next = vec![0u8; STREAM_CHUNK_LENGTH];
+    let mut length = read_chunk(reader, &mut chunk)?;
+    loop {
+        let next_length = read_chunk(reader, &mut next)?;
+        if next_length == 0 {
+            let ciphertext = encryptor
+                .encrypt_last(&chunk[..length])
+                .expect("chunk is within the AES-GCM length limit");
+            writer
+                .write_all(&ciphertext)
+                .map_err(|err| Error::Io(err.kind()))?;
+            break;
+        }
+
+        // The STREAM counter only overflows past 2^32 chunks, which is 256 TiB
+        let ciphertext = encryptor
+            .encrypt_next(&chunk[..length])
+            .expect("stream is within the STREAM chunk limit");
+        writer
+            .write_all(&ciphertext)
+            .map_err(|err| Error::Io(err.kind()))?;
+        std::mem::swap(&mut chunk, &mut next);
+        length = next_length;
+    }
+
+    writer.flush().map_err(|err| Error::Io(err.kind()))?;
+    Ok(bid_public_key)
+}
+
+/// Verifies and decrypts a ciphertext produced by [`encrypt_stream`], writing the message to `writer` one
+/// chunk at a time.
+///
+/// Each chunk is written only after its tag is verified, but an error can still occur after earlier chunks
+/// were written, e.g. if the ciphertext was truncated, in which case everything written must be discarded.
+/// Errors with [`Error::InvalidTag`] if any chunk fails its tag check.
+pub fn decrypt_stream<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    bid_public_key: G1,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<(), Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+    let shared_secret = derive_shared_secret(bid_public_key, private_key)?;
+
+    let (cipher, prefix) = stream_cipher(&shared_secret, salt, domain);
+    let mut decryptor = DecryptorBE32::from_aead(cipher, prefix.as_slice().into());
+
+    // Read one chunk ahead, as encrypt_stream does, to find the last chunk
+    let mut chunk = vec![0u8; STREAM_CHUNK_LENGTH + TAG_LENGTH];
+    let mut next = vec![0u8; STREAM_CHUNK_LENGTH + TAG_LENGTH];
+    let mut length = read_chunk(reader, &mut chunk)?;
+    loop {
+        let next_length = read_chunk(reader, &mut next)?;
+        if next_length == 0 {
+            let message = decryptor
+                .decrypt_last(&chunk[..length])
+                .map_err(|_| Error::InvalidTag)?;
+            writer
+                .write_all(&message)
+                .map_err(|err| Error::Io(err.kind()))?;
+            break;
+        }
+
+        let message = decryptor
+            .decrypt_next(&chunk[..length])
+            .map_err(|_| Error::InvalidTag)?;
+        writer
+            .write_all(&message)
+            .map_err(|err| Error::Io(err.kind()))?;
+        std::mem::swap(&mut chunk, &mut next);
+        length = next_length;
+    }
+
+    writer.flush().map_err(|err| Error::Io(err.kind()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_then_decrypt_recovers_message_and_rejects_tampering() {
+        let private_key = ScalarField::from(2u64);
+        let public_key = derive_public_key(private_key).unwrap();
+        let salt = [7u8; 32];
+        let message = (0u8..40).collect::<Vec<u8>>();
+
+        let encrypted = encrypt(&message, public_key, ScalarField::from(3u64), &salt, &[]).unwrap();
+        assert_eq!(encrypted.ciphertext.len(), message.len() + TAG_LENGTH);
+        let decrypted = decrypt(
+            &encrypted.ciphertext,
+            encrypted.bid_public_key,
+            private_key,
+            &salt,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(decrypted, message);
+
+        let mut ciphertext = encrypted.ciphertext;
+        ciphertext[0] ^= 1;
+        let err = decrypt(
+            &ciphertext,
+            encrypted.bid_public_key,
+            private_key,
+            &salt,
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::InvalidTag);
+    }
+
+    #[test]
+    fn stream_roundtrips_and_rejects_truncation() {
+        let private_key = ScalarField::from(2u64);
+        let public_key = derive_public_key(private_key).unwrap();
+        let salt = [7u8; 32];
+        // Two full chunks and a partial one
+        let message = (0..2 * STREAM_CHUNK_LENGTH + 100)
+            .map(|i| i as u8)
+            .collect::<Vec<u8>>();
+
+        let mut ciphertext = Vec::new();
+        let bid_public_key = encrypt_stream(
+            &mut message.as_slice(),
+            &mut ciphertext,
+            public_key,
+            ScalarField::from(3u64),
+            &salt,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(ciphertext.len(), message.len() + 3 * TAG_LENGTH);
+
+        let mut decrypted = Vec::new();
+        decrypt_stream(
+            &mut ciphertext.as_slice(),
+            &mut decrypted,
+            bid_public_key,
+            private_key,
+            &salt,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(decrypted, message);
+
+        // Dropping the last chunk leaves a chunk that was not sealed as the last one
+        let truncated = &ciphertext[..2 * (STREAM_CHUNK_LENGTH + TAG_LENGTH)];
+        let err = decrypt_stream(
+            &mut &truncated[..],
+            &mut Vec::new(),
+            bid_public_key,
+            private_key,
+            &salt,
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::InvalidTag);
+    }
+}
@@ -0,0 +1,665 @@
+//! ECIES on the bn254 (aka alt_bn128) curve, matching the `ECIES` library used by the Axis contracts.
+//!
+//! The symmetric key for each 32-byte block of the message is derived from the x coordinate of the
+//! shared secret point and a salt using keccak256, and the block is encrypted by XORing it with the key.
+//! Single-block messages produce the same ciphertext as the contract implementation.
+
+use std::fmt;
+
+use ark_bn254::{Fq as BaseField, Fr as ScalarField, G1Affine as G1};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInt, BigInteger, Field, PrimeField, UniformRand, Zero};
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+use subtle::ConstantTimeEq;
+use tiny_keccak::{Hasher, Keccak};
+use tracing::{debug, debug_span};
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod g2;
+pub mod gcm;
+#[cfg(any(feature = "wasm", feature = "ffi"))]
+mod strings;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Errors returned by the ECIES functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The point does not satisfy the bn254 curve equation.
+    NotOnCurve,
+    /// The point is not in the bn254 prime-order subgroup.
+    NotInSubgroup,
+    /// The private key is zero, which maps to the point at infinity.
+    ZeroPrivateKey,
+    /// The compressed point has an invalid prefix, an out-of-range x coordinate, or no matching y coordinate.
+    InvalidCompressedPoint,
+    /// The authentication tag does not match the ciphertext.
+    InvalidTag,
+    /// The shared secret point is the point at infinity, which has no x coordinate to derive a key from.
+    IdentitySharedSecret,
+    /// Reading the input or writing the output of a streaming function failed.
+    Io(std::io::ErrorKind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotOnCurve => write!(f, "point is not on the bn254 curve"),
+            Error::NotInSubgroup => write!(f, "point is not in the bn254 prime-order subgroup"),
+            Error::ZeroPrivateKey => {
+                write!(
+                    f,
+                    "private key must be non-zero (it maps to the point at infinity)"
+                )
+            }
+            Error::InvalidCompressedPoint => {
+                write!(f, "compressed point is not a valid bn254 G1 point")
+            }
+            Error::InvalidTag => write!(f, "authentication tag does not match the ciphertext"),
+            Error::IdentitySharedSecret => {
+                write!(f, "shared secret is the point at infinity")
+            }
+            Error::Io(kind) => write!(f, "I/O error: {}", kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// An encrypted message along with the bid public key the recipient needs to decrypt it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedBid {
+    /// The encrypted message, which has the same length as the plaintext.
+    pub ciphertext: Vec<u8>,
+    /// The public key corresponding to the bid private key used to encrypt the message.
+    pub bid_public_key: G1,
+}
+
+/// Formats bytes as a 0x-prefixed hex string when recorded in a tracing event.
+pub(crate) struct Hex<'a>(pub(crate) &'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+/// Serializes a field element, or any other 256-bit integer, as 32 big-endian bytes left-padded with zeros.
+///
+/// This is the one serialization used for coordinates, scalars and salts, so values with high zero bytes
+/// stay aligned to the 32-byte word.
+pub fn to_bytes32(value: impl Into<BigInt<4>>) -> [u8; 32] {
+    let bytes = value.into().to_bytes_be();
+
+    let mut output = [0u8; 32];
+    output[32 - bytes.len()..].copy_from_slice(&bytes);
+    output
+}
+
+/// Parses 32 big-endian bytes as a field element, returning `None` if the value is not below the field modulus.
+fn field_from_bytes32<F: PrimeField<BigInt = BigInt<4>>>(bytes: &[u8; 32]) -> Option<F> {
+    let mut limbs = [0u64; 4];
+    for (index, chunk) in bytes.chunks(8).enumerate() {
+        limbs[3 - index] = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    F::from_bigint(BigInt(limbs))
+}
+
+/// Parses 32 big-endian bytes as a base field element, returning `None` if the value is not below the field modulus.
+pub fn base_field_from_bytes32(bytes: &[u8; 32]) -> Option<BaseField> {
+    field_from_bytes32(bytes)
+}
+
+/// Parses 32 big-endian bytes as a scalar, returning `None` if the value is not below the group order.
+pub fn scalar_field_from_bytes32(bytes: &[u8; 32]) -> Option<ScalarField> {
+    field_from_bytes32(bytes)
+}
+
+/// Hashes the data with keccak256.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Constructs a G1 point from its coordinates.
+///
+/// Errors if the point is not on the curve or not in the prime-order subgroup.
+pub fn point_from_coordinates(x: BaseField, y: BaseField) -> Result<G1, Error> {
+    let point = G1::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::NotOnCurve);
+    }
+
+    // bn254 G1 has cofactor 1, so this always passes for on-curve points, but it keeps the check explicit
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+/// Serializes a G1 point in the 33-byte compressed form.
+///
+/// The layout is a prefix byte of `0x02` (y is even) or `0x03` (y is odd), followed by the x coordinate as 32 big-endian bytes.
+pub fn compress(point: G1) -> [u8; 33] {
+    let mut output = [0u8; 33];
+    output[0] = if point.y.into_bigint().is_odd() {
+        0x03
+    } else {
+        0x02
+    };
+    output[1..].copy_from_slice(&to_bytes32(point.x));
+    output
+}
+
+/// Parses a G1 point from the 33-byte compressed form produced by [`compress`].
+///
+/// The y coordinate is recovered by solving `y^2 = x^3 + 3` and selecting the root with the parity given by the prefix.
+pub fn decompress(bytes: &[u8; 33]) -> Result<G1, Error> {
+    let odd = match bytes[0] {
+        0x02 => false,
+        0x03 => true,
+        _ => return Err(Error::InvalidCompressedPoint),
+    };
+
+    let x = base_field_from_bytes32(bytes[1..].try_into().unwrap())
+        .ok_or(Error::InvalidCompressedPoint)?;
+    let mut y = (x * x * x + BaseField::from(3u64))
+        .sqrt()
+        .ok_or(Error::InvalidCompressedPoint)?;
+    if y.into_bigint().is_odd() != odd {
+        y = -y;
+    }
+
+    point_from_coordinates(x, y)
+}
+
+/// Calculates the public key corresponding to a private key.
+pub fn derive_public_key(private_key: ScalarField) -> Result<G1, Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+
+    Ok((G1::generator() * private_key).into_affine())
+}
+
+/// Generates a random keypair using a CSPRNG, resampling the degenerate zero scalar.
+pub fn generate_keypair() -> (ScalarField, G1) {
+    generate_keypair_with_rng(&mut OsRng)
+}
+
+/// Generates a random keypair from the given CSPRNG, resampling the degenerate zero scalar.
+///
+/// A seeded RNG makes the keypair reproducible, e.g. in tests.
+pub fn generate_keypair_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (ScalarField, G1) {
+    let mut private_key = ScalarField::rand(rng);
+    while private_key.is_zero() {
+        private_key = ScalarField::rand(rng);
+    }
+
+    (private_key, (G1::generator() * private_key).into_affine())
+}
+
+/// Generates a random 32-byte salt using a CSPRNG.
+pub fn generate_salt() -> [u8; 32] {
+    generate_salt_with_rng(&mut OsRng)
+}
+
+/// Generates a random 32-byte salt from the given CSPRNG.
+pub fn generate_salt_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; 32] {
+    let mut salt = [0u8; 32];
+    rng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Derives the Ethereum-style address of a public key.
+///
+/// This is the last 20 bytes of the keccak256 hash of the 64-byte uncompressed coordinates `x || y`.
+pub fn derive_address(public_key: G1) -> [u8; 20] {
+    let hash = keccak256(&[to_bytes32(public_key.x), to_bytes32(public_key.y)].concat());
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Derives the shared secret point from a public key and a private key.
+///
+/// Errors if the shared secret point is the point at infinity, whose affine x coordinate would be a predictable zero.
+pub fn derive_shared_point(public_key: G1, private_key: ScalarField) -> Result<G1, Error> {
+    let shared_point = (public_key * private_key).into_affine();
+    if shared_point.is_zero() {
+        return Err(Error::IdentitySharedSecret);
+    }
+    Ok(shared_point)
+}
+
+/// Derives the shared secret from a public key and a private key.
+///
+/// Returns the x coordinate of the shared secret point as 32 big-endian bytes.
+pub fn derive_shared_secret(public_key: G1, private_key: ScalarField) -> Result<[u8; 32], Error> {
+    let _span = debug_span!("derive_shared_secret").entered();
+    let shared_secret = to_bytes32(derive_shared_point(public_key, private_key)?.x);
+    debug!(shared_secret_x = %Hex(&shared_secret));
+    Ok(shared_secret)
+}
+
+/// Builds the keccak256 preimage of the symmetric key for a 32-byte block of the message.
+///
+/// With an empty domain, the first block uses `shared_secret_x || salt`, which matches the contract,
+/// and each subsequent block appends the block index as a 32-byte big-endian counter:
+/// `shared_secret_x || salt || counter`.
+///
+/// A non-empty domain is prefixed with its length as a 32-byte big-endian integer, and every block
+/// includes the counter: `shared_secret_x || salt || len(domain) || domain || counter`. Without the
+/// prefix, a domain `d` at one counter would hash the same preimage as the domain `d || counter` at
+/// the first block.
+///
+/// The domain separates keys derived from the same shared secret for different purposes, so the
+/// encryptor and decryptor must agree on it.
+///
+/// The shared secret x coordinate is 32 bytes for G1 keys, or 64 bytes for the G2 keys in [`g2`].
+pub fn symmetric_key_preimage(
+    shared_secret: &[u8],
+    salt: &[u8; 32],
+    domain: &[u8],
+    counter: u64,
+) -> Vec<u8> {
+    let to_word = |value: u64| {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    };
+
+    let mut preimage = [shared_secret, salt.as_slice()].concat();
+    if !domain.is_empty() {
+        preimage.extend_from_slice(&to_word(domain.len() as u64));
+        preimage.extend_from_slice(domain);
+    }
+    if counter > 0 || !domain.is_empty() {
+        preimage.extend_from_slice(&to_word(counter));
+    }
+    preimage
+}
+
+/// Derives the symmetric key for a 32-byte block of the message by hashing its [`symmetric_key_preimage`].
+pub fn derive_symmetric_key(
+    shared_secret: &[u8],
+    salt: &[u8; 32],
+    domain: &[u8],
+    counter: u64,
+) -> [u8; 32] {
+    let _span = debug_span!("derive_symmetric_key", counter).entered();
+    let preimage = symmetric_key_preimage(shared_secret, salt, domain, counter);
+    let symmetric_key = keccak256(&preimage);
+    debug!(preimage = %Hex(&preimage), symmetric_key = %Hex(&symmetric_key));
+    symmetric_key
+}
+
+/// XORs a block with a key of at least the same length, in constant time.
+///
+/// Every byte of the block is processed with no data-dependent branches or early exits.
+fn xor_block(output: &mut [u8], block: &[u8], key: &[u8; 32]) {
+    for index in 0..block.len() {
+        output[index] = block[index] ^ key[index];
+    }
+}
+
+/// Derives the key used to authenticate ciphertexts as `keccak256(shared_secret_x || salt || "mac")`.
+///
+/// The domain-separating suffix keeps it independent of the encryption keystream.
+pub fn derive_mac_key(shared_secret: &[u8; 32], salt: &[u8; 32]) -> [u8; 32] {
+    keccak256(&[shared_secret.as_slice(), salt.as_slice(), b"mac"].concat())
+}
+
+/// Computes the authentication tag of a ciphertext as `keccak256(mac_key || ciphertext)`.
+///
+/// keccak256 is not vulnerable to length extension, so the keyed prefix construction is a sound MAC.
+pub fn compute_tag(mac_key: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    keccak256(&[mac_key.as_slice(), ciphertext].concat())
+}
+
+/// Compares two byte strings in constant time with respect to their contents.
+///
+/// Only the lengths, which are public, can short-circuit the comparison.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// XORs the data with the keystream derived from the shared secret, the salt, and the domain.
+///
+/// The final block is XORed with a truncated key, so the output has the same length as the input.
+pub fn apply_keystream(
+    data: &[u8],
+    shared_secret: &[u8],
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Vec<u8> {
+    let mut output = vec![0u8; data.len()];
+    for ((block, output), counter) in data.chunks(32).zip(output.chunks_mut(32)).zip(0u64..) {
+        let symmetric_key = derive_symmetric_key(shared_secret, salt, domain, counter);
+        let _span = debug_span!("xor_block", counter, length = block.len()).entered();
+        xor_block(output, block, &symmetric_key);
+        debug!(input = %Hex(block), output = %Hex(output));
+    }
+    output
+}
+
+/// Encrypts a message to the recipient public key using the bid private key and the salt.
+pub fn encrypt(
+    message: &[u8],
+    public_key: G1,
+    bid_private_key: ScalarField,
+    salt: &[u8; 32],
+) -> Result<EncryptedBid, Error> {
+    encrypt_with_domain(message, public_key, bid_private_key, salt, &[])
+}
+
+/// Encrypts a message as [`encrypt`] does, with the symmetric keys derived under a domain.
+///
+/// See [`symmetric_key_preimage`]. An empty domain gives the same ciphertext as [`encrypt`].
+pub fn encrypt_with_domain(
+    message: &[u8],
+    public_key: G1,
+    bid_private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<EncryptedBid, Error> {
+    // Calculate the bid public key using the bid private key
+    let bid_public_key = derive_public_key(bid_private_key)?;
+
+    // Calculate the shared secret from the recipient public key and the bid private key
+    let shared_secret = derive_shared_secret(public_key, bid_private_key)?;
+
+    Ok(EncryptedBid {
+        ciphertext: apply_keystream(message, &shared_secret, salt, domain),
+        bid_public_key,
+    })
+}
+
+/// Decrypts a ciphertext using the bid public key, the recipient private key, and the salt.
+pub fn decrypt(
+    ciphertext: &[u8],
+    bid_public_key: G1,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+) -> Result<Vec<u8>, Error> {
+    decrypt_with_domain(ciphertext, bid_public_key, private_key, salt, &[])
+}
+
+/// Decrypts a ciphertext produced by [`encrypt_with_domain`] under the same domain.
+pub fn decrypt_with_domain(
+    ciphertext: &[u8],
+    bid_public_key: G1,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+
+    // Calculate the shared secret from the bid public key and the recipient private key
+    let shared_secret = derive_shared_secret(bid_public_key, private_key)?;
+
+    Ok(apply_keystream(ciphertext, &shared_secret, salt, domain))
+}
+
+/// Encrypts a message as [`encrypt_with_domain`] does, also returning a tag that authenticates the ciphertext.
+///
+/// The MAC key does not depend on the domain.
+pub fn encrypt_authenticated(
+    message: &[u8],
+    public_key: G1,
+    bid_private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<(EncryptedBid, [u8; 32]), Error> {
+    let encrypted = encrypt_with_domain(message, public_key, bid_private_key, salt, domain)?;
+
+    // Calculate the tag over the ciphertext using the MAC key derived from the same shared secret
+    let shared_secret = derive_shared_secret(public_key, bid_private_key)?;
+    let tag = compute_tag(&derive_mac_key(&shared_secret, salt), &encrypted.ciphertext);
+
+    Ok((encrypted, tag))
+}
+
+/// Verifies the tag of a ciphertext produced by [`encrypt_authenticated`] and decrypts it.
+///
+/// Errors before decrypting anything if the tag does not match.
+pub fn decrypt_authenticated(
+    ciphertext: &[u8],
+    tag: &[u8; 32],
+    bid_public_key: G1,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if private_key.is_zero() {
+        return Err(Error::ZeroPrivateKey);
+    }
+
+    // Recompute the tag and compare it in constant time
+    let shared_secret = derive_shared_secret(bid_public_key, private_key)?;
+    let expected = compute_tag(&derive_mac_key(&shared_secret, salt), ciphertext);
+    if !constant_time_eq(&expected, tag) {
+        return Err(Error::InvalidTag);
+    }
+
+    Ok(apply_keystream(ciphertext, &shared_secret, salt, domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Samples a non-zero scalar from 32 random bytes
+    fn scalar() -> impl Strategy<Value = ScalarField> {
+        any::<[u8; 32]>()
+            .prop_map(|bytes| ScalarField::from_be_bytes_mod_order(&bytes))
+            .prop_filter("scalar must be non-zero", |scalar| !scalar.is_zero())
+    }
+
+    proptest! {
+        #[test]
+        fn encrypt_then_decrypt_recovers_random_messages(
+            message in proptest::collection::vec(any::<u8>(), 0..160),
+            salt in any::<[u8; 32]>(),
+            private_key in scalar(),
+            bid_private_key in scalar(),
+        ) {
+            let public_key = derive_public_key(private_key).unwrap();
+            let encrypted = encrypt(&message, public_key, bid_private_key, &salt).unwrap();
+            prop_assert_eq!(
+                encrypted.bid_public_key,
+                (G1::generator() * bid_private_key).into_affine()
+            );
+
+            let decrypted = decrypt(
+                &encrypted.ciphertext,
+                encrypted.bid_public_key,
+                private_key,
+                &salt,
+            )
+            .unwrap();
+            prop_assert_eq!(decrypted, message);
+        }
+    }
+
+    #[test]
+    fn encrypt_then_decrypt_recovers_message() {
+        let private_key = ScalarField::from(2u64);
+        let public_key = derive_public_key(private_key).unwrap();
+        let salt = [7u8; 32];
+
+        // Use a message longer than one block to exercise the counter
+        let message = (0u8..80).collect::<Vec<u8>>();
+        let encrypted = encrypt(&message, public_key, ScalarField::from(3u64), &salt).unwrap();
+        assert_eq!(encrypted.ciphertext.len(), message.len());
+
+        let decrypted = decrypt(
+            &encrypted.ciphertext,
+            encrypted.bid_public_key,
+            private_key,
+            &salt,
+        )
+        .unwrap();
+        assert_eq!(decrypted, message);
+    }
+
+    #[test]
+    fn authenticated_encrypt_then_decrypt_recovers_message() {
+        let private_key = ScalarField::from(2u64);
+        let public_key = derive_public_key(private_key).unwrap();
+        let salt = [7u8; 32];
+        let message = [42u8; 32];
+
+        let (encrypted, tag) =
+            encrypt_authenticated(&message, public_key, ScalarField::from(3u64), &salt, &[])
+                .unwrap();
+        let decrypted = decrypt_authenticated(
+            &encrypted.ciphertext,
+            &tag,
+            encrypted.bid_public_key,
+            private_key,
+            &salt,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(decrypted, message);
+    }
+
+    #[test]
+    fn authenticated_decrypt_rejects_tampered_ciphertext() {
+        let private_key = ScalarField::from(2u64);
+        let public_key = derive_public_key(private_key).unwrap();
+        let salt = [7u8; 32];
+
+        let (encrypted, tag) =
+            encrypt_authenticated(&[42u8; 32], public_key, ScalarField::from(3u64), &salt, &[])
+                .unwrap();
+        let mut ciphertext = encrypted.ciphertext;
+        ciphertext[31] ^= 1;
+
+        let err = decrypt_authenticated(
+            &ciphertext,
+            &tag,
+            encrypted.bid_public_key,
+            private_key,
+            &salt,
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::InvalidTag);
+    }
+
+    #[test]
+    fn apply_keystream_matches_reference_xor() {
+        let shared_secret = [5u8; 32];
+        let salt = [9u8; 32];
+        let data = (0u8..75).collect::<Vec<u8>>();
+
+        // The straightforward zip/map XOR the constant-time routine replaced
+        let expected = data
+            .chunks(32)
+            .zip(0u64..)
+            .flat_map(|(block, counter)| {
+                let symmetric_key = derive_symmetric_key(&shared_secret, &salt, &[], counter);
+                block
+                    .iter()
+                    .zip(symmetric_key.iter())
+                    .map(|(a, b)| a ^ b)
+                    .collect::<Vec<u8>>()
+            })
+            .collect::<Vec<u8>>();
+
+        let output = apply_keystream(&data, &shared_secret, &salt, &[]);
+        assert_eq!(output, expected);
+        assert!(constant_time_eq(&output, &expected));
+        assert!(!constant_time_eq(&output, &data));
+    }
+
+    #[test]
+    fn to_bytes32_left_pads_values_with_high_zero_bytes() {
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+        assert_eq!(to_bytes32(ScalarField::from(1u64)), expected);
+        assert_eq!(to_bytes32(BaseField::zero()), [0u8; 32]);
+
+        // 31 significant bytes, so only the first byte is padding
+        let mut expected = [0xff; 32];
+        expected[0] = 0;
+        let value = BigInt::<4>::new([u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 8]);
+        assert_eq!(to_bytes32(value), expected);
+    }
+
+    #[test]
+    fn different_domains_derive_different_symmetric_keys() {
+        let shared_secret = derive_shared_secret(
+            derive_public_key(ScalarField::from(2u64)).unwrap(),
+            ScalarField::from(3u64),
+        )
+        .unwrap();
+        let salt = [1u8; 32];
+
+        let bids = derive_symmetric_key(&shared_secret, &salt, b"bids", 0);
+        let metadata = derive_symmetric_key(&shared_secret, &salt, b"metadata", 0);
+        assert_ne!(bids, metadata);
+        assert_ne!(bids, derive_symmetric_key(&shared_secret, &salt, &[], 0));
+    }
+
+    #[test]
+    fn domain_cannot_absorb_the_counter() {
+        let shared_secret = [2u8; 32];
+        let salt = [1u8; 32];
+
+        // Without a length prefix, "bids" at counter 1 would hash the same preimage as "bids" || be32(1) at counter 0
+        let mut extended = b"bids".to_vec();
+        extended.extend_from_slice(&[0u8; 31]);
+        extended.push(1);
+        assert_ne!(
+            derive_symmetric_key(&shared_secret, &salt, b"bids", 1),
+            derive_symmetric_key(&shared_secret, &salt, &extended, 0)
+        );
+    }
+
+    #[test]
+    fn derive_shared_secret_rejects_identity() {
+        let err = derive_shared_secret(G1::zero(), ScalarField::from(2u64)).unwrap_err();
+        assert_eq!(err, Error::IdentitySharedSecret);
+    }
+
+    #[test]
+    fn compress_then_decompress_recovers_point() {
+        // Cover both y parities
+        for scalar in 1u64..=8 {
+            let point = derive_public_key(ScalarField::from(scalar)).unwrap();
+            let compressed = compress(point);
+            assert_eq!(compressed[1..], to_bytes32(point.x));
+            assert_eq!(decompress(&compressed).unwrap(), point);
+        }
+    }
+
+    #[test]
+    fn seeded_rng_generates_reproducible_keypair_and_salt() {
+        use rand::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(7);
+        let (private_key, public_key) = generate_keypair_with_rng(&mut rng);
+        let salt = generate_salt_with_rng(&mut rng);
+        assert_eq!(public_key, derive_public_key(private_key).unwrap());
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(7);
+        assert_eq!(
+            generate_keypair_with_rng(&mut rng),
+            (private_key, public_key)
+        );
+        assert_eq!(generate_salt_with_rng(&mut rng), salt);
+    }
+}
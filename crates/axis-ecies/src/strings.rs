@@ -0,0 +1,130 @@
+//! Hex string inputs and outputs shared by the `wasm` and `ffi` bindings.
+//!
+//! All values are 0x-prefixed hex strings. The output of [`encrypt`] has the same layout as the
+//! `ecies-cli encrypt` abi output: the ciphertext followed by the bid public key coordinates.
+
+use ark_bn254::{Fr as ScalarField, G1Affine as G1};
+
+use crate::{base_field_from_bytes32, scalar_field_from_bytes32, to_bytes32};
+
+/// Decodes a 0x-prefixed hex string, treating an odd number of digits as left-padded with a zero nibble.
+fn decode_hex(value: &str, name: &str) -> Result<Vec<u8>, String> {
+    let digits = value
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("{} must be 0x-prefixed hex", name))?;
+    // Check the digits up front, since slicing non-ASCII input by byte index would panic
+    // and from_str_radix would accept a leading '+'
+    if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(format!("{} is not valid hex", name));
+    }
+    let digits = match digits.len() % 2 {
+        1 => format!("0{}", digits),
+        _ => digits.to_string(),
+    };
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&digits[index..index + 2], 16)
+                .map_err(|_| format!("{} is not valid hex", name))
+        })
+        .collect()
+}
+
+/// Encodes bytes as a 0x-prefixed hex string.
+fn encode_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", digits)
+}
+
+/// Decodes a hex string of at most 32 bytes, left-padded to 32 bytes.
+fn decode_bytes32(value: &str, name: &str) -> Result<[u8; 32], String> {
+    let bytes = decode_hex(value, name)?;
+    if bytes.len() > 32 {
+        return Err(format!("{} must be at most 32 bytes", name));
+    }
+
+    let mut output = [0u8; 32];
+    output[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(output)
+}
+
+/// Decodes a public key from its hex coordinates.
+fn decode_point(x: &str, y: &str, name: &str) -> Result<G1, String> {
+    let invalid = || format!("{} is not a valid bn254 G1 point", name);
+    let x = base_field_from_bytes32(&decode_bytes32(x, name)?).ok_or_else(invalid)?;
+    let y = base_field_from_bytes32(&decode_bytes32(y, name)?).ok_or_else(invalid)?;
+
+    crate::point_from_coordinates(x, y).map_err(|err| format!("{}: {}", name, err))
+}
+
+/// Decodes a private key, rejecting scalars that are not below the group order.
+fn decode_scalar(value: &str, name: &str) -> Result<ScalarField, String> {
+    scalar_field_from_bytes32(&decode_bytes32(value, name)?)
+        .ok_or_else(|| format!("{}: scalar exceeds bn254 group order", name))
+}
+
+/// Encrypts a message to the recipient public key using the bid private key and the salt.
+///
+/// Messages of up to 32 bytes are left-padded to one 32-byte block, as the CLI does.
+pub fn encrypt(
+    message: &str,
+    public_key_x: &str,
+    public_key_y: &str,
+    bid_private_key: &str,
+    salt: &str,
+) -> Result<String, String> {
+    let mut message = decode_hex(message, "message")?;
+    if message.len() < 32 {
+        let mut padded = vec![0u8; 32 - message.len()];
+        padded.append(&mut message);
+        message = padded;
+    }
+
+    let public_key = decode_point(public_key_x, public_key_y, "public key")?;
+    let bid_private_key = decode_scalar(bid_private_key, "bid private key")?;
+    let salt = decode_bytes32(salt, "salt")?;
+
+    let encrypted = crate::encrypt(&message, public_key, bid_private_key, &salt)
+        .map_err(|err| err.to_string())?;
+
+    Ok(encode_hex(
+        &[
+            encrypted.ciphertext,
+            to_bytes32(encrypted.bid_public_key.x).to_vec(),
+            to_bytes32(encrypted.bid_public_key.y).to_vec(),
+        ]
+        .concat(),
+    ))
+}
+
+/// Derives the x coordinate of the shared secret point from a public key and a private key.
+#[cfg(feature = "wasm")]
+pub fn derive_shared_secret(
+    public_key_x: &str,
+    public_key_y: &str,
+    private_key: &str,
+) -> Result<String, String> {
+    let public_key = decode_point(public_key_x, public_key_y, "public key")?;
+    let private_key = decode_scalar(private_key, "private key")?;
+
+    let shared_secret =
+        crate::derive_shared_secret(public_key, private_key).map_err(|err| err.to_string())?;
+    Ok(encode_hex(&shared_secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_hex_rejects_non_hex_digits() {
+        assert_eq!(decode_hex("0xabc", "value").unwrap(), [0x0a, 0xbc]);
+        for value in ["0x\u{e9}0", "0x+f", "0x-1", "0x 1"] {
+            assert_eq!(
+                decode_hex(value, "value"),
+                Err("value is not valid hex".to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! JavaScript bindings for encrypting bids in the browser, enabled by the `wasm` feature.
+//!
+//! All inputs and outputs are 0x-prefixed hex strings. The output of [`encrypt`] has the same layout as the
+//! `ecies-cli encrypt` abi output: the ciphertext followed by the bid public key coordinates.
+//!
+//! The package is built from the `axis-ecies-bindings` crate, e.g. with `pnpm run build:ecies-wasm`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::strings;
+
+/// Encrypts a message to the recipient public key using the bid private key and the salt.
+///
+/// Messages of up to 32 bytes are left-padded to one 32-byte block, as the CLI does.
+#[wasm_bindgen]
+pub fn encrypt(
+    message: &str,
+    public_key_x: &str,
+    public_key_y: &str,
+    bid_private_key: &str,
+    salt: &str,
+) -> Result<String, JsError> {
+    strings::encrypt(message, public_key_x, public_key_y, bid_private_key, salt)
+        .map_err(|err| JsError::new(&err))
+}
+
+/// Derives the x coordinate of the shared secret point from a public key and a private key.
+#[wasm_bindgen(js_name = deriveSharedSecret)]
+pub fn derive_shared_secret(
+    public_key_x: &str,
+    public_key_y: &str,
+    private_key: &str,
+) -> Result<String, JsError> {
+    strings::derive_shared_secret(public_key_x, public_key_y, private_key)
+        .map_err(|err| JsError::new(&err))
+}
@@ -0,0 +1,33 @@
+// Smoke test comparing the wasm bindings with the native CLI
+//
+// Run with `pnpm run test:ecies-wasm`, which builds the wasm package with wasm-pack and the native CLI first
+
+import assert from "node:assert/strict";
+import { execFileSync } from "node:child_process";
+import { createRequire } from "node:module";
+import { fileURLToPath } from "node:url";
+
+const require = createRequire(import.meta.url);
+const { encrypt, deriveSharedSecret } = require("../../../axis-ecies-bindings/pkg/axis_ecies.js");
+const cli = fileURLToPath(new URL("../../../ecies-cli/target/debug/ecies-cli", import.meta.url));
+
+// The recipient public key is 2G, matching the ECIES Solidity tests
+const publicKeyX = "0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3";
+const publicKeyY = "0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+const bidPrivateKey = "0x03";
+const salt = "0x01";
+
+for (const message of ["0x01", "0x" + "ab".repeat(80)]) {
+    const native = execFileSync(cli, ["encrypt", message, publicKeyX, publicKeyY, bidPrivateKey, salt])
+        .toString()
+        .trim();
+    assert.equal(encrypt(message, publicKeyX, publicKeyY, bidPrivateKey, salt), native);
+}
+
+// The shared secret x coordinate of 3 * 2G, i.e. 6G
+assert.equal(
+    deriveSharedSecret(publicKeyX, publicKeyY, bidPrivateKey),
+    "0x09f4ca411a3f52f4e0792fd9e792779856719215d3b32a762afe3d5b8c684af9",
+);
+
+console.log("OK");
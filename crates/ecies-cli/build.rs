@@ -0,0 +1,24 @@
+// Build script embedding the git commit the CLI was built from, for the `version` subcommand
+
+use std::process::Command;
+
+// Helper function to run git and return its trimmed stdout, if it succeeds
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+fn main() {
+    // Rebuild when HEAD moves, so the embedded commit stays current
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        println!("cargo:rerun-if-changed={}/logs/HEAD", git_dir);
+    }
+
+    // Builds outside a git checkout, e.g. from a source archive, report the commit as unknown
+    let commit = git(&["rev-parse", "--short=12", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=ECIES_CLI_GIT_COMMIT={}", commit);
+}
@@ -0,0 +1,179 @@
+// Helpers for converting between the hex and decimal strings used on the command line and bytes
+
+use ark_ff::BigInt;
+use axis_ecies::to_bytes32;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap::ValueEnum;
+use ethers::{
+    types::{Bytes, U256},
+    utils::hex,
+};
+use num_bigint::BigUint;
+use std::sync::OnceLock;
+
+// Encodings for byte strings on the command line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Encoding {
+    /// 0x-prefixed hex
+    #[default]
+    Hex,
+    /// Standard base64 with padding and no prefix
+    Base64,
+}
+
+// The encoding selected for this invocation, set once after parsing the arguments
+static ENCODING: OnceLock<Encoding> = OnceLock::new();
+
+// Helper function to select the encoding used by bytes_to_string and string_to_bytes
+pub fn set_encoding(encoding: Encoding) {
+    ENCODING.set(encoding).expect("encoding is only set once");
+}
+
+fn encoding() -> Encoding {
+    ENCODING.get().copied().unwrap_or_default()
+}
+
+// Helper function to convert bytes to a string in the selected encoding
+pub fn bytes_to_string(bytes: &[u8]) -> String {
+    match encoding() {
+        Encoding::Hex => format!("0x{}", hex::encode(bytes)),
+        Encoding::Base64 => BASE64.encode(bytes),
+    }
+}
+
+// Helper function to parse a string in the selected encoding into bytes
+pub fn string_to_bytes(value: &str) -> Result<Vec<u8>, String> {
+    match encoding() {
+        Encoding::Hex => hex_to_bytes(value),
+        Encoding::Base64 => BASE64
+            .decode(value)
+            .map_err(|err| format!("'{}' is not valid base64: {}", value, err)),
+    }
+}
+
+// Helper function to parse a 0x-prefixed hex string into bytes
+// Rejects a missing prefix, an odd number of digits, and non-hex characters
+fn hex_to_bytes(value: &str) -> Result<Vec<u8>, String> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .ok_or_else(|| format!("'{}' is not 0x-prefixed hex", value))?;
+    if digits.len() % 2 == 1 {
+        return Err(format!("'{}' has an odd number of hex digits", value));
+    }
+
+    hex::decode(digits).map_err(|err| format!("'{}' is not valid hex: {}", value, err))
+}
+
+// Helper function to parse a big integer from either a decimal or a 0x-prefixed hexadecimal string
+pub fn parse_biguint(value: &str) -> Result<BigUint, String> {
+    let (digits, radix) = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => (hex, 16),
+        None => (value, 10),
+    };
+
+    BigUint::parse_bytes(digits.as_bytes(), radix).ok_or_else(|| {
+        format!(
+            "'{}' is not a valid decimal or 0x-prefixed hex integer",
+            value
+        )
+    })
+}
+
+// Helper function to parse a uint256 from either a decimal or a 0x-prefixed hexadecimal string
+pub fn parse_u256(value: &str) -> Result<U256, String> {
+    Ok(U256::from_big_endian(&parse_bytes32(value)?))
+}
+
+// Helper function to parse a decimal or 0x-prefixed hex integer that fits in a uint128
+pub fn parse_u128(value: &str) -> Result<u128, String> {
+    let value = parse_biguint(value)?;
+    u128::try_from(&value).map_err(|_| format!("{} does not fit in a uint128", value))
+}
+
+// Helper function to parse an Fq2 element from two comma-separated integers
+// The parts are in EIP-197 order, as used by the EVM pairing precompile: the imaginary part c1, then the real part c0
+pub fn parse_fq2(value: &str) -> Result<[BigUint; 2], String> {
+    match value.split(',').collect::<Vec<_>>()[..] {
+        [c1, c0] => Ok([parse_biguint(c1.trim())?, parse_biguint(c0.trim())?]),
+        _ => Err(format!(
+            "'{}' is not an Fq2 element of the form c1,c0",
+            value
+        )),
+    }
+}
+
+// Helper function to parse a message or ciphertext into bytes
+// Decimal and 0x-prefixed hex values of up to 32 bytes are left-padded to one 32-byte block, as before
+// Longer values keep their exact length, so hex input should be used to preserve leading zero bytes
+// With the base64 encoding, the value is always decoded as base64 and then padded in the same way
+pub fn parse_message(value: &str) -> Result<Bytes, String> {
+    let mut bytes = parse_unpadded_message(value)?;
+    if bytes.len() < 32 {
+        let mut padded = vec![0u8; 32 - bytes.len()];
+        padded.append(&mut bytes);
+        bytes = padded;
+    }
+
+    Ok(Bytes::from(bytes))
+}
+
+// Helper function to parse a message as parse_message does, without padding it to a 32-byte block
+// Hex and base64 values keep their exact bytes, and decimal values are their minimal big-endian bytes
+pub fn parse_unpadded_message(value: &str) -> Result<Vec<u8>, String> {
+    let prefixed = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"));
+    match (encoding(), prefixed) {
+        (Encoding::Base64, _) => string_to_bytes(value),
+        (Encoding::Hex, Some(digits)) => {
+            // Allow an odd number of hex digits by treating the value as left-padded with a zero nibble
+            let digits = if digits.len() % 2 == 1 {
+                format!("0{}", digits)
+            } else {
+                digits.to_string()
+            };
+            hex::decode(digits).map_err(|err| format!("'{}' is not valid hex: {}", value, err))
+        }
+        (Encoding::Hex, None) => Ok(parse_biguint(value)?.to_bytes_be()),
+    }
+}
+
+// Helper function to parse raw message bytes from a 0x-prefixed hex string, such as a hash or an address
+// Unlike parse_message, decimal input is not accepted, so the value is never mistaken for an integer
+// Values of up to 32 bytes are treated as big-endian and left-padded to one 32-byte block
+pub fn parse_message_bytes(value: &str) -> Result<Bytes, String> {
+    let mut bytes = hex_to_bytes(value)?;
+    if bytes.len() < 32 {
+        let mut padded = vec![0u8; 32 - bytes.len()];
+        padded.append(&mut bytes);
+        bytes = padded;
+    }
+
+    Ok(Bytes::from(bytes))
+}
+
+// Helper function to parse a 33-byte compressed G1 point in the selected encoding
+pub fn parse_compressed_point(value: &str) -> Result<[u8; 33], String> {
+    string_to_bytes(value)?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected 33 bytes, got {}", bytes.len()))
+}
+
+// Helper function to parse a 64-byte public key blob in the selected encoding, as x || y with each coordinate as 32 big-endian bytes
+pub fn parse_public_key_blob(value: &str) -> Result<[u8; 64], String> {
+    string_to_bytes(value)?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected 64 bytes, got {}", bytes.len()))
+}
+
+// Helper function to parse a decimal or 0x-prefixed hex integer, such as a salt, into 32 big-endian bytes
+pub fn parse_bytes32(value: &str) -> Result<[u8; 32], String> {
+    let value = parse_biguint(value)?;
+    BigInt::<4>::try_from(value.clone())
+        .map(to_bytes32)
+        .map_err(|_| format!("{} does not fit in a uint256", value))
+}
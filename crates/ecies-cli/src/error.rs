@@ -0,0 +1,192 @@
+// Errors returned by the CLI, and the exit code each of them maps to
+
+use clap::ValueEnum;
+use std::process::ExitCode;
+
+// Exit codes, so scripts can tell bad input apart from failed cryptography
+// 0 is success, and clap exits with 2 for errors found while parsing the arguments
+// Failures to read an input or write an output also exit with 2, reported with the "io" kind
+pub const EXIT_USAGE: u8 = 2;
+pub const EXIT_VALIDATION: u8 = 3;
+pub const EXIT_CRYPTO: u8 = 4;
+// validate-pubkey exits with one code per failed check
+pub const EXIT_NOT_ON_CURVE: u8 = 5;
+pub const EXIT_NOT_IN_SUBGROUP: u8 = 6;
+pub const EXIT_IDENTITY: u8 = 7;
+
+// Formats for errors printed to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// A human-readable message
+    #[default]
+    Text,
+    /// One JSON object with the message, the kind of error, and the exit code
+    Json,
+}
+
+impl ErrorFormat {
+    // Helper function to find --error-format in the raw arguments
+    // The format is needed to report errors in the arguments themselves, before they have been parsed
+    // An unrecognised value falls back to text, and clap then reports it as its own error
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = match arg.strip_prefix("--error-format") {
+                Some("") => args.next(),
+                Some(value) => value.strip_prefix('=').map(str::to_string),
+                None => continue,
+            };
+            return value
+                .and_then(|value| ErrorFormat::from_str(&value, false).ok())
+                .unwrap_or_default();
+        }
+
+        ErrorFormat::default()
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+// The checks run by validate-pubkey, in the order they are run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicKeyCheck {
+    NotIdentity,
+    OnCurve,
+    InSubgroup,
+}
+
+impl PublicKeyCheck {
+    pub const ALL: [PublicKeyCheck; 3] = [
+        PublicKeyCheck::NotIdentity,
+        PublicKeyCheck::OnCurve,
+        PublicKeyCheck::InSubgroup,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PublicKeyCheck::NotIdentity => "not identity",
+            PublicKeyCheck::OnCurve => "on curve",
+            PublicKeyCheck::InSubgroup => "in subgroup",
+        }
+    }
+
+    fn exit_code(self) -> u8 {
+        match self {
+            PublicKeyCheck::NotIdentity => EXIT_IDENTITY,
+            PublicKeyCheck::OnCurve => EXIT_NOT_ON_CURVE,
+            PublicKeyCheck::InSubgroup => EXIT_NOT_IN_SUBGROUP,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    // The command line could not be parsed, rendered by clap with its usage and suggestions
+    #[error(transparent)]
+    Args(#[from] clap::Error),
+    // A value or combination of values was rejected after parsing, e.g. from stdin or a params file
+    #[error("{0}")]
+    Parse(String),
+    // Reading an input or writing an output failed
+    #[error("{0}")]
+    Io(String),
+    // A value parsed but is not valid for its use, such as an off-curve point or an out-of-range scalar
+    #[error("{0}")]
+    Validation(String),
+    // A cryptographic check failed, such as a tag or roundtrip mismatch
+    #[error("{0}")]
+    Crypto(String),
+    // validate-pubkey found the public key failed a check, with the report of every check
+    #[error("public key failed validation\n  {report}")]
+    PublicKey {
+        check: PublicKeyCheck,
+        report: String,
+    },
+}
+
+impl Error {
+    pub fn parse(message: impl std::fmt::Display) -> Self {
+        Error::Parse(message.to_string())
+    }
+
+    pub fn io(message: impl std::fmt::Display) -> Self {
+        Error::Io(message.to_string())
+    }
+
+    pub fn validation(message: impl std::fmt::Display) -> Self {
+        Error::Validation(message.to_string())
+    }
+
+    pub fn crypto(message: impl std::fmt::Display) -> Self {
+        Error::Crypto(message.to_string())
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Args(err) => err.exit_code() as u8,
+            Error::Parse(_) | Error::Io(_) => EXIT_USAGE,
+            Error::Validation(_) => EXIT_VALIDATION,
+            Error::Crypto(_) => EXIT_CRYPTO,
+            Error::PublicKey { check, .. } => check.exit_code(),
+        }
+    }
+
+    // The kind of error reported by --error-format json
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Args(_) | Error::Parse(_) => "parse",
+            Error::Io(_) => "io",
+            Error::Validation(_) | Error::PublicKey { .. } => "validation",
+            Error::Crypto(_) => "crypto",
+        }
+    }
+
+    // The message without clap's "error: " prefix and usage tip
+    fn message(&self) -> String {
+        match self {
+            Error::Args(err) => {
+                let rendered = err.render().to_string();
+                let first_line = rendered.lines().next().unwrap_or_default();
+                first_line
+                    .strip_prefix("error: ")
+                    .unwrap_or(first_line)
+                    .to_string()
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    // Helper function to print the error to stderr in the given format and return the exit code for it
+    // clap prints its own errors in the text format, and always prints --help and --version, which exit with 0
+    pub fn report(&self, format: ErrorFormat) -> ExitCode {
+        match (self, format) {
+            (Error::Args(err), _) if err.exit_code() == 0 => {
+                let _ = err.print();
+            }
+            (Error::Args(err), ErrorFormat::Text) => {
+                let _ = err.print();
+            }
+            (_, ErrorFormat::Text) => eprintln!("error: {}", self),
+            (_, ErrorFormat::Json) => eprintln!(
+                "{}",
+                serde_json::json!({
+                    "error": self.message(),
+                    "kind": self.kind(),
+                    "code": self.exit_code(),
+                })
+            ),
+        }
+        ExitCode::from(self.exit_code())
+    }
+}
+
+// A failed tag check is a crypto failure rather than invalid input
+impl From<axis_ecies::Error> for Error {
+    fn from(err: axis_ecies::Error) -> Self {
+        match err {
+            axis_ecies::Error::InvalidTag => Error::crypto(err),
+            axis_ecies::Error::Io(_) => Error::io(err),
+            _ => Error::validation(err),
+        }
+    }
+}
@@ -6,67 +6,1395 @@
 
 // Dependencies
 
-use ark_bn254::{Fq as BaseField, Fr as ScalarField, G1Affine as G1};
-use ark_ec::{AffineRepr, CurveGroup};
-use clap::{error::Result, Parser, Subcommand};
+mod encoding;
+mod error;
+mod repl;
+mod rpc;
+
+use ark_bn254::{Fq as BaseField, Fq2, Fr as ScalarField, G1Affine as G1, G2Affine as G2};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInt, PrimeField, UniformRand, Zero};
+use axis_ecies::to_bytes32;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use encoding::{
+    bytes_to_string, parse_biguint, parse_bytes32, parse_compressed_point, parse_fq2,
+    parse_message, parse_message_bytes, parse_public_key_blob, parse_u128, parse_u256,
+    parse_unpadded_message, Encoding,
+};
+use error::{Error, ErrorFormat, PublicKeyCheck, Result};
 use ethers::{
-    types::{Address, U256},
+    types::{Address, Bytes, U256},
     utils::hex,
 };
 use num_bigint::BigUint;
+use rand::{rngs::OsRng, CryptoRng, RngCore, SeedableRng};
+use rayon::prelude::*;
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+// Helper function to read a value from stdin and parse it, trimming the trailing newline
+fn read_stdin<T>(name: &str, parse: fn(&str) -> std::result::Result<T, String>) -> Result<T> {
+    if repl::running() {
+        return Err(Error::parse(format!(
+            "cannot read {} from stdin in the repl, which reads commands from it",
+            name
+        )));
+    }
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|err| Error::io(format!("failed to read {} from stdin: {}", name, err)))?;
+
+    parse(input.trim_end_matches(['\r', '\n']))
+        .map_err(|err| Error::parse(format!("invalid {} on stdin: {}", name, err)))
+}
+
+// Positional arguments, some of which may instead be supplied through an option
+// Values are consumed in order, so the remaining positionals shift left when one is supplied elsewhere
+// Arguments with no positional value left fall back to the params file, keyed by the camelCase argument name
+struct Positionals {
+    values: std::vec::IntoIter<String>,
+    params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Positionals {
+    fn new(values: impl IntoIterator<Item = Option<String>>) -> Self {
+        Self {
+            values: values.into_iter().flatten().collect::<Vec<_>>().into_iter(),
+            params: serde_json::Map::new(),
+        }
+    }
+
+    // Load fallback values for the arguments from a JSON params file, if provided
+    fn with_params(mut self, path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            self.params = read_json(path)?;
+        }
+        Ok(self)
+    }
+
+    // Parse the next positional value, falling back to the params file, and erroring if neither provides it
+    fn next<T>(
+        &mut self,
+        name: &str,
+        parse: fn(&str) -> std::result::Result<T, String>,
+    ) -> Result<T> {
+        let key = camel_case(name);
+        let param = self.params.remove(&key);
+        let value = match (self.values.next(), param) {
+            (Some(value), _) => value,
+            (None, Some(serde_json::Value::String(value))) => value,
+            (None, Some(serde_json::Value::Number(value))) => value.to_string(),
+            (None, Some(_)) => {
+                return Err(Error::parse(format!(
+                    "params key '{}' must be a string or number",
+                    key
+                )))
+            }
+            (None, None) => {
+                return Err(Error::parse(format!(
+                    "the following required argument was not provided: <{}>",
+                    name
+                )))
+            }
+        };
+
+        parse(&value).map_err(|err| {
+            Error::parse(format!(
+                "invalid value '{}' for '<{}>': {}",
+                value, name, err
+            ))
+        })
+    }
+
+    // Error if there are positional values or params keys left over
+    fn finish(mut self) -> Result<()> {
+        if let Some(value) = self.values.next() {
+            return Err(Error::parse(format!(
+                "unexpected argument '{}' found",
+                value
+            )));
+        }
+        match self.params.keys().next() {
+            Some(key) => Err(Error::parse(format!(
+                "unexpected key '{}' in params file",
+                key
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+// Helper function to convert a snake_case argument name to its camelCase params file key
+fn camel_case(name: &str) -> String {
+    let mut parts = name.split('_');
+    let mut key = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            key.extend(first.to_uppercase());
+            key.push_str(chars.as_str());
+        }
+    }
+    key
+}
+
+// Helper function to calculate the salt the contract uses for a bid
+// This is keccak256(abi.encodePacked(uint96 lot_id, address bidder, uint96 amount))
+// The lot_id and amount are 32-byte big-endian words, which are sliced to their low 12 bytes
+fn packed_salt(lot_id: &[u8; 32], bidder: Address, amount: &[u8; 32]) -> [u8; 32] {
+    // We have to carefully pack this so the hash is accurate
+    let preimage = [
+        lot_id[20..].to_vec(),
+        bidder.as_bytes().to_vec(),
+        amount[20..].to_vec(),
+    ]
+    .concat();
+
+    ethers::utils::keccak256(preimage)
+}
+
+// Helper function to calculate the salt of a bid as packed_salt does, for the uint96 lot id and amount the contract uses
+// Errors if either value does not fit in a uint96, since it would not match the contract's salt
+fn bid_salt(lot_id: &BigUint, bidder: Address, amount: &BigUint) -> Result<[u8; 32]> {
+    for (name, value) in [("lot id", lot_id), ("amount", amount)] {
+        if value.bits() > 96 {
+            return Err(Error::validation(format!(
+                "{} {} does not fit in a uint96",
+                name, value
+            )));
+        }
+    }
+
+    Ok(packed_salt(
+        &to_bytes32(BigInt::<4>::try_from(lot_id.clone()).expect("lot id fits in a uint96")),
+        bidder,
+        &to_bytes32(BigInt::<4>::try_from(amount.clone()).expect("amount fits in a uint96")),
+    ))
+}
+
+// Helper function to pack an EMP bid message from its amount out and a 128-bit seed
+// The message is seed || uint128(amount_out - seed), so the masked amount has no telltale leading zeroes
+fn pack_emp_message(amount_out: u128, seed: u128) -> [u8; 32] {
+    let mut message = [0u8; 32];
+    message[..16].copy_from_slice(&seed.to_be_bytes());
+    message[16..].copy_from_slice(&amount_out.wrapping_sub(seed).to_be_bytes());
+    message
+}
+
+// Helper function to unpack an EMP bid message into its seed, masked value and amount out, as EMP.decryptBid does
+fn unpack_emp_message(message: &[u8; 32]) -> (u128, u128, u128) {
+    let seed = u128::from_be_bytes(message[..16].try_into().unwrap());
+    let masked_value = u128::from_be_bytes(message[16..].try_into().unwrap());
+    (seed, masked_value, masked_value.wrapping_add(seed))
+}
+
+// Set from --strict for the command being run, so the key, salt and message helpers can apply the strict checks
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+// Helper function to check whether the command is running with --strict
+fn strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+// Helper function to use an all-zero salt in place of the salt positional, warning that it was used
+// Errors under --strict, since a fixed salt makes the symmetric key depend on the shared secret alone
+fn zero_salt_value(quiet: bool) -> Result<[u8; 32]> {
+    if strict() {
+        return Err(Error::validation(
+            "--zero-salt is not allowed with --strict",
+        ));
+    }
+    if !quiet {
+        eprintln!("warning: using an all-zero salt");
+    }
+    Ok([0u8; 32])
+}
+
+// Helper function to construct a G1 point from its coordinates
+// Errors if the point is not on the curve or not in the prime-order subgroup, or under --strict if it is the generator
+fn g1_from_coordinates(x: BigUint, y: BigUint, name: &str) -> Result<G1> {
+    let x = base_field_from_biguint(x, &format!("{} x", name))?;
+    let y = base_field_from_biguint(y, &format!("{} y", name))?;
+
+    let point = axis_ecies::point_from_coordinates(x, y).map_err(|err| {
+        let reason = match err {
+            axis_ecies::Error::NotInSubgroup => "is not in the bn254 G1 prime-order subgroup",
+            _ => "is not a valid bn254 G1 point",
+        };
+        Error::validation(format!("{} (x, y) {}", name, reason))
+    })?;
+
+    // The contract's ECIES.isValid rejects the generator, so a bid using it could not be decrypted on-chain
+    if strict() && point == G1::generator() {
+        return Err(Error::validation(format!(
+            "{} (x, y) is the bn254 G1 generator, which --strict rejects",
+            name
+        )));
+    }
+
+    Ok(point)
+}
+
+// Helper function to construct a G2 point from its Fq2 coordinates, each given as [c1, c0]
+// Errors if the point is not on the curve or not in the prime-order subgroup
+fn g2_from_coordinates(x: [BigUint; 2], y: [BigUint; 2], name: &str) -> Result<G2> {
+    let [x_c1, x_c0] = x;
+    let [y_c1, y_c0] = y;
+    let x = Fq2::new(
+        base_field_from_biguint(x_c0, &format!("{} x c0", name))?,
+        base_field_from_biguint(x_c1, &format!("{} x c1", name))?,
+    );
+    let y = Fq2::new(
+        base_field_from_biguint(y_c0, &format!("{} y c0", name))?,
+        base_field_from_biguint(y_c1, &format!("{} y c1", name))?,
+    );
+
+    axis_ecies::g2::point_from_coordinates(x, y).map_err(|err| {
+        let reason = match err {
+            axis_ecies::Error::NotInSubgroup => "is not in the bn254 G2 prime-order subgroup",
+            _ => "is not a valid bn254 G2 point",
+        };
+        Error::validation(format!("{} (x, y) {}", name, reason))
+    })
+}
+
+// A public key in either of the bn254 groups
+enum PublicKey {
+    G1(G1),
+    G2(G2),
+}
+
+// Helper function to convert a coordinate to the bn254 base field
+// Errors instead of reducing it mod the field modulus, which would silently construct a different point
+fn base_field_from_biguint(value: BigUint, name: &str) -> Result<BaseField> {
+    if value >= BigUint::from(BaseField::MODULUS) {
+        return Err(Error::validation(format!(
+            "{}: coordinate exceeds bn254 base field modulus",
+            name
+        )));
+    }
+
+    Ok(BaseField::from(value))
+}
+
+// Helper function to convert a scalar to the ark-bn254 type
+// Errors if the scalar is not already reduced, since ark would otherwise reduce it silently and diverge from the contract,
+// or under --strict if it is 1
+fn scalar_from_biguint(value: BigUint, name: &str) -> Result<ScalarField> {
+    if value >= BigUint::from(ScalarField::MODULUS) {
+        return Err(Error::validation(format!(
+            "{}: scalar exceeds bn254 group order",
+            name
+        )));
+    }
+    // A key of 1 has the generator as its public key, which the contract's ECIES.isValid rejects
+    if strict() && value == BigUint::from(1u8) {
+        return Err(Error::validation(format!(
+            "{}: a key of 1 derives the bn254 G1 generator, which --strict rejects",
+            name
+        )));
+    }
+
+    Ok(ScalarField::from(value))
+}
+
+// Helper function to fit a message into the single 32-byte block the contract's ECIES.encrypt accepts
+// Longer messages error unless truncation is acknowledged, in which case the low-order 32 bytes are kept
+// Under --strict, longer messages always error, since truncation silently drops the high-order bytes
+fn single_block_message(message: Bytes, truncate: bool) -> Result<Bytes> {
+    if message.len() <= 32 {
+        return Ok(message);
+    }
+    if truncate && strict() {
+        return Err(Error::validation(format!(
+            "message is {} bytes, and --truncate is not allowed with --strict",
+            message.len()
+        )));
+    }
+    if !truncate {
+        return Err(Error::validation(
+            format!(
+                "message is {} bytes, which exceeds the 32-byte single block (pass --truncate to keep the low-order 32 bytes)",
+                message.len()
+            ),
+        ));
+    }
+
+    Ok(Bytes::from(message[message.len() - 32..].to_vec()))
+}
+
+// Helper function to log the shared secret point and the key schedule for a message to stderr
+// Errors if the shared secret point is the point at infinity
+fn log_key_schedule(
+    public_key: G1,
+    private_key: ScalarField,
+    salt: &[u8; 32],
+    domain: &[u8],
+    length: usize,
+) -> Result<()> {
+    let shared_point = axis_ecies::derive_shared_point(public_key, private_key)?;
+    let shared_secret = to_bytes32(shared_point.x);
+    eprintln!("shared secret point x: {}", bytes_to_string(&shared_secret));
+    eprintln!(
+        "shared secret point y: {}",
+        bytes_to_string(&to_bytes32(shared_point.y))
+    );
+    eprintln!("salt: {}", bytes_to_string(salt));
+    if !domain.is_empty() {
+        eprintln!("domain: {}", bytes_to_string(domain));
+    }
+
+    // One symmetric key per 32-byte block of the message
+    for counter in 0..length.div_ceil(32) as u64 {
+        let preimage = axis_ecies::symmetric_key_preimage(&shared_secret, salt, domain, counter);
+        eprintln!("block {} preimage: {}", counter, bytes_to_string(&preimage));
+        eprintln!(
+            "block {} symmetric key: {}",
+            counter,
+            bytes_to_string(&axis_ecies::keccak256(&preimage))
+        );
+    }
+
+    Ok(())
+}
+
+// Helper function to error with both values if a decrypted message does not equal the expected one
+// The comparison is constant-time, so the check does not leak how much of the message matched
+fn check_expected_message(message: &[u8], expected: Option<&[u8]>) -> Result<()> {
+    match expected {
+        Some(expected) if !axis_ecies::constant_time_eq(message, expected) => {
+            Err(Error::crypto(format!(
+                "decrypted message does not equal the expected value\n  expected: {}\n  actual:   {}",
+                bytes_to_string(expected),
+                bytes_to_string(message)
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Helper function to calculate the fingerprint of a bid public key, used as a short index key
+// This is keccak256(x || y) truncated to 16 bytes, which is not cryptographically binding
+fn bid_public_key_fingerprint(bid_public_key: G1) -> [u8; 16] {
+    let preimage = [to_bytes32(bid_public_key.x), to_bytes32(bid_public_key.y)].concat();
+    axis_ecies::keccak256(&preimage)[..16].try_into().unwrap()
+}
+
+// Output formats for encryption results
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// A single hex-encoded blob of ciphertext || bid public key x || bid public key y
+    Abi,
+    /// A JSON object with the ciphertext and bid public key coordinates as separate hex fields
+    Json,
+    /// Solidity statements declaring the ciphertext as a bytes hex literal and the coordinates as uint256 literals
+    Solidity,
+}
+
+// Radixes that integers can be converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Radix {
+    /// Decimal
+    Dec,
+    /// 0x-prefixed hex, zero-padded to 32 bytes
+    Hex,
+}
+
+// Sides that --pad-to can pad the message on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PadSide {
+    /// message || 0x80 || zeros
+    Right,
+    /// zeros || 0x80 || message
+    Left,
+}
+
+// Helper function to pad a message to a fixed length with a 0x80 marker, so --unpad can strip the padding exactly
+// The marker always takes one byte, so the message must be shorter than the target length
+fn pad_message(message: &[u8], length: usize, side: PadSide) -> Result<Vec<u8>> {
+    if message.len() >= length {
+        return Err(Error::validation(format!(
+            "message is {} bytes, which does not fit in --pad-to {} with the 1-byte padding marker",
+            message.len(),
+            length
+        )));
+    }
+
+    let zeros = vec![0u8; length - message.len() - 1];
+    Ok(match side {
+        PadSide::Right => [message, &[0x80], &zeros[..]].concat(),
+        PadSide::Left => [&zeros[..], &[0x80], message].concat(),
+    })
+}
+
+// Helper function to strip the padding added by pad_message, checking for its marker
+fn unpad_message(message: &[u8], side: PadSide) -> Result<Vec<u8>> {
+    let unpadded = match side {
+        PadSide::Right => {
+            let end = message.iter().rposition(|byte| *byte != 0);
+            end.filter(|&end| message[end] == 0x80)
+                .map(|end| &message[..end])
+        }
+        PadSide::Left => {
+            let start = message.iter().position(|byte| *byte != 0);
+            start
+                .filter(|&start| message[start] == 0x80)
+                .map(|start| &message[start + 1..])
+        }
+    };
+
+    unpadded.map(<[u8]>::to_vec).ok_or_else(|| {
+        Error::validation("decrypted message does not end in --pad-to padding on the given side")
+    })
+}
 
-// Helper function to convert bytes to a hex-encoded string
-fn bytes_to_string(bytes: &[u8]) -> String {
-    format!("0x{}", hex::encode(bytes))
+// Symmetric layers that the message can be encrypted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Cipher {
+    /// XOR with keccak256-derived keys, as the ECIES contract library does
+    Xor,
+    /// AES-256-GCM keyed by the first keccak256-derived key, with the 16-byte tag after the ciphertext (off-chain only)
+    ///
+    /// The nonce is derived from the salt, so encrypting requires --random-salt: reusing a bid private key and salt would reuse the nonce.
+    AesGcm,
+}
+
+// Groups that public keys can belong to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Group {
+    /// bn254 G1, as used by the ECIES contract library
+    G1,
+    /// bn254 G2, with each coordinate given as two comma-separated integers c1,c0 (EIP-197 order)
+    G2,
+}
+
+// Helper function to error if an option is used with a mode that does not support it, such as --group g2
+fn check_unsupported_options(mode: &str, options: &[(&str, bool)]) -> Result<()> {
+    match options.iter().find(|(_, used)| *used) {
+        Some((name, _)) => Err(Error::parse(format!(
+            "the argument '{}' cannot be used with '{}'",
+            name, mode
+        ))),
+        None => Ok(()),
+    }
+}
+
+// Helper function to format an encrypted bid for output
+// When compressed, the bid public key is the 33-byte compressed point instead of the two 32-byte coordinates
+// When authenticated, the tag is appended after the bid public key
+fn format_encrypted_bid(
+    encrypted: axis_ecies::EncryptedBid,
+    format: OutputFormat,
+    compressed: bool,
+    tag: Option<[u8; 32]>,
+) -> String {
+    match format {
+        // Combine the ciphertext and the bid public key into a hex-encoded string to return
+        // This is abi-encoded for single-block messages, otherwise the ciphertext occupies the leading bytes
+        OutputFormat::Abi => {
+            let mut output = encrypted.ciphertext.clone();
+            match compressed {
+                true => output.extend_from_slice(&axis_ecies::compress(encrypted.bid_public_key)),
+                false => {
+                    output.extend_from_slice(&to_bytes32(encrypted.bid_public_key.x));
+                    output.extend_from_slice(&to_bytes32(encrypted.bid_public_key.y));
+                }
+            }
+            if let Some(tag) = tag {
+                output.extend_from_slice(&tag);
+            }
+            bytes_to_string(&output)
+        }
+        OutputFormat::Json => encrypted_bid_json(&encrypted, compressed, tag).to_string(),
+        // Literals are always hex, since Solidity has no base64 literals
+        OutputFormat::Solidity => {
+            let mut lines = vec![format!(
+                "bytes memory ciphertext = hex\"{}\";",
+                hex::encode(&encrypted.ciphertext)
+            )];
+            match compressed {
+                true => lines.push(format!(
+                    "bytes memory bidPublicKey = hex\"{}\";",
+                    hex::encode(axis_ecies::compress(encrypted.bid_public_key))
+                )),
+                false => {
+                    lines.push(format!(
+                        "uint256 bidPublicKeyX = 0x{};",
+                        hex::encode(to_bytes32(encrypted.bid_public_key.x))
+                    ));
+                    lines.push(format!(
+                        "uint256 bidPublicKeyY = 0x{};",
+                        hex::encode(to_bytes32(encrypted.bid_public_key.y))
+                    ));
+                }
+            }
+            if let Some(tag) = tag {
+                lines.push(format!("bytes32 tag = 0x{};", hex::encode(tag)));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+// Helper function to format an encrypted bid with a G2 bid public key for output
+// Each coordinate is serialized as 64 bytes, c1 || c0
+fn format_encrypted_g2_bid(
+    encrypted: axis_ecies::g2::EncryptedBid,
+    format: OutputFormat,
+) -> String {
+    let x_bytes = axis_ecies::g2::to_bytes64(encrypted.bid_public_key.x);
+    let y_bytes = axis_ecies::g2::to_bytes64(encrypted.bid_public_key.y);
+
+    match format {
+        OutputFormat::Abi => {
+            bytes_to_string(&[encrypted.ciphertext, x_bytes.to_vec(), y_bytes.to_vec()].concat())
+        }
+        OutputFormat::Json => serde_json::json!({
+            "ciphertext": bytes_to_string(&encrypted.ciphertext),
+            "bidPublicKeyX": bytes_to_string(&x_bytes),
+            "bidPublicKeyY": bytes_to_string(&y_bytes),
+        })
+        .to_string(),
+        // Each coordinate is a uint256[2] in the same c1, c0 order
+        OutputFormat::Solidity => {
+            let coordinate = |name: &str, bytes: &[u8; 64]| {
+                format!(
+                    "uint256[2] memory {} = [uint256(0x{}), 0x{}];",
+                    name,
+                    hex::encode(&bytes[..32]),
+                    hex::encode(&bytes[32..])
+                )
+            };
+            [
+                format!(
+                    "bytes memory ciphertext = hex\"{}\";",
+                    hex::encode(&encrypted.ciphertext)
+                ),
+                coordinate("bidPublicKeyX", &x_bytes),
+                coordinate("bidPublicKeyY", &y_bytes),
+            ]
+            .join("\n")
+        }
+    }
+}
+
+// Helper function to convert an encrypted bid to a JSON object with hex fields
+fn encrypted_bid_json(
+    encrypted: &axis_ecies::EncryptedBid,
+    compressed: bool,
+    tag: Option<[u8; 32]>,
+) -> serde_json::Value {
+    let mut output = match compressed {
+        true => serde_json::json!({
+            "ciphertext": bytes_to_string(&encrypted.ciphertext),
+            "bidPublicKey": bytes_to_string(&axis_ecies::compress(encrypted.bid_public_key)),
+        }),
+        false => serde_json::json!({
+            "ciphertext": bytes_to_string(&encrypted.ciphertext),
+            "bidPublicKeyX": bytes_to_string(&to_bytes32(encrypted.bid_public_key.x)),
+            "bidPublicKeyY": bytes_to_string(&to_bytes32(encrypted.bid_public_key.y)),
+        }),
+    };
+    if let Some(tag) = tag {
+        output["tag"] = serde_json::json!(bytes_to_string(&tag));
+    }
+    output
+}
+
+// A record to encrypt in a batch, with each value as a decimal or 0x-prefixed hex string
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct EncryptRecord {
+    message: String,
+    bid_private_key: String,
+    salt: String,
+}
+
+// A case in a test-vector file, tagged by its scheme in the "type" field
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum VectorCase {
+    Ecies(Box<EciesVector>),
+    // RSA cases are recognised so they can be reported as skipped, but ecies-cli cannot run them
+    Rsa,
+}
+
+// An ECIES test vector: the encrypt inputs and expected outputs, with each value as a decimal or 0x-prefixed hex string
+// With a private key, the expected ciphertext is also decrypted and checked against the message
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct EciesVector {
+    name: Option<String>,
+    message: String,
+    public_key_x: String,
+    public_key_y: String,
+    bid_private_key: String,
+    salt: String,
+    #[serde(default)]
+    domain: String,
+    ciphertext: String,
+    bid_public_key_x: String,
+    bid_public_key_y: String,
+    private_key: Option<String>,
+}
+
+// A recipient public key, with each coordinate as a decimal or 0x-prefixed hex string
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipientRecord {
+    x: String,
+    y: String,
+}
+
+// Helper function to split an abi-encoded blob into an encrypted bid
+// Errors if the blob is not exactly 96 bytes or the bid public key is not a valid G1 point
+fn decode_encrypted_bid(blob: &[u8]) -> Result<axis_ecies::EncryptedBid> {
+    if blob.len() != 96 {
+        return Err(Error::parse(format!(
+            "blob must be exactly 96 bytes, got {}",
+            blob.len()
+        )));
+    }
+
+    // Construct bid public key from coordinates
+    // Will error if the point is not on the curve
+    let bid_public_key = g1_from_coordinates(
+        BigUint::from_bytes_be(&blob[32..64]),
+        BigUint::from_bytes_be(&blob[64..]),
+        "bid public key",
+    )?;
+    Ok(axis_ecies::EncryptedBid {
+        ciphertext: blob[..32].to_vec(),
+        bid_public_key,
+    })
+}
+
+// Helper function to read and parse a JSON file
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| Error::io(format!("failed to read {}: {}", path.display(), err)))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| Error::parse(format!("failed to parse {}: {}", path.display(), err)))
+}
+
+// Helper function to run an ECIES test vector through encrypt, and decrypt if it has a private key
+// Returns the reason for the first mismatch or malformed value
+fn verify_ecies_vector(vector: &EciesVector) -> std::result::Result<(), String> {
+    let message =
+        parse_message(&vector.message).map_err(|err| format!("invalid message: {}", err))?;
+    let public_key = g1_from_coordinates(
+        parse_biguint(&vector.public_key_x)?,
+        parse_biguint(&vector.public_key_y)?,
+        "public key",
+    )
+    .map_err(|err| err.to_string())?;
+    let bid_private_key =
+        scalar_from_biguint(parse_biguint(&vector.bid_private_key)?, "bid private key")
+            .map_err(|err| err.to_string())?;
+    let salt = parse_bytes32(&vector.salt).map_err(|err| format!("invalid salt: {}", err))?;
+    let ciphertext =
+        parse_message(&vector.ciphertext).map_err(|err| format!("invalid ciphertext: {}", err))?;
+    let bid_public_key = g1_from_coordinates(
+        parse_biguint(&vector.bid_public_key_x)?,
+        parse_biguint(&vector.bid_public_key_y)?,
+        "bid public key",
+    )
+    .map_err(|err| err.to_string())?;
+
+    let encrypted = axis_ecies::encrypt_with_domain(
+        &message,
+        public_key,
+        bid_private_key,
+        &salt,
+        vector.domain.as_bytes(),
+    )
+    .map_err(|err| format!("encrypt failed: {}", err))?;
+    if encrypted.bid_public_key != bid_public_key {
+        return Err("bid public key mismatch".to_string());
+    }
+    if encrypted.ciphertext != ciphertext.as_ref() {
+        return Err(format!(
+            "ciphertext mismatch: got {}",
+            bytes_to_string(&encrypted.ciphertext)
+        ));
+    }
+
+    if let Some(private_key) = &vector.private_key {
+        let private_key = scalar_from_biguint(parse_biguint(private_key)?, "private key")
+            .map_err(|err| err.to_string())?;
+        let decrypted = axis_ecies::decrypt_with_domain(
+            &ciphertext,
+            bid_public_key,
+            private_key,
+            &salt,
+            vector.domain.as_bytes(),
+        )
+        .map_err(|err| format!("decrypt failed: {}", err))?;
+        if decrypted != message.as_ref() {
+            return Err(format!(
+                "decrypted message mismatch: got {}",
+                bytes_to_string(&decrypted)
+            ));
+        }
+    }
+
+    Ok(())
 }
 
+// Helper function to encrypt one batch-encrypt record, reporting errors with the index of the record
+fn encrypt_record(
+    index: usize,
+    record: &EncryptRecord,
+    public_key: G1,
+    format: OutputFormat,
+) -> Result<String> {
+    let record_error = |field: &str, err: String| {
+        Error::parse(format!("record {}: invalid {}: {}", index, field, err))
+    };
+
+    let message = parse_message(&record.message).map_err(|err| record_error("message", err))?;
+    let bid_private_key =
+        parse_biguint(&record.bid_private_key).map_err(|err| record_error("bidPrivateKey", err))?;
+    let salt = parse_bytes32(&record.salt).map_err(|err| record_error("salt", err))?;
+
+    let encrypted = axis_ecies::encrypt(
+        &message,
+        public_key,
+        scalar_from_biguint(bid_private_key, &format!("record {}: bidPrivateKey", index))?,
+        &salt,
+    )
+    .map_err(|err| record_error("bidPrivateKey", err.to_string()))?;
+
+    Ok(format_encrypted_bid(encrypted, format, false, None))
+}
+
+// Helper function to format a keypair as a JSON object, as keygen prints it
+fn keypair_json(private_key: ScalarField, public_key: G1) -> serde_json::Value {
+    serde_json::json!({
+        "privateKey": bytes_to_string(&to_bytes32(private_key)),
+        "publicKeyX": bytes_to_string(&to_bytes32(public_key.x)),
+        "publicKeyY": bytes_to_string(&to_bytes32(public_key.y)),
+    })
+}
+
+// Helper function to encrypt a message to each recipient in a file with a single fresh bid keypair
+// Each entry is the same as single-recipient encryption with that public key and the shared bid private key
+fn encrypt_to_recipients(
+    path: &Path,
+    message: &[u8],
+    salt: &[u8; 32],
+    domain: &[u8],
+    compressed: bool,
+    authenticated: bool,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<String> {
+    let records: Vec<RecipientRecord> = read_json(path)?;
+    let (bid_private_key, _) = axis_ecies::generate_keypair_with_rng(rng);
+
+    let mut outputs = Vec::with_capacity(records.len());
+    for (index, record) in records.iter().enumerate() {
+        let record_error = |field: &str, err: String| {
+            Error::parse(format!("recipient {}: invalid {}: {}", index, field, err))
+        };
+
+        let x = parse_biguint(&record.x).map_err(|err| record_error("x", err))?;
+        let y = parse_biguint(&record.y).map_err(|err| record_error("y", err))?;
+        let public_key = g1_from_coordinates(x, y, &format!("recipient {} public key", index))?;
+
+        let (encrypted, tag) = match authenticated {
+            true => {
+                let (encrypted, tag) = axis_ecies::encrypt_authenticated(
+                    message,
+                    public_key,
+                    bid_private_key,
+                    salt,
+                    domain,
+                )?;
+                (encrypted, Some(tag))
+            }
+            false => (
+                axis_ecies::encrypt_with_domain(
+                    message,
+                    public_key,
+                    bid_private_key,
+                    salt,
+                    domain,
+                )?,
+                None,
+            ),
+        };
+
+        let mut output = encrypted_bid_json(&encrypted, compressed, tag);
+        output["recipient"] = serde_json::json!(index);
+        outputs.push(output);
+    }
+
+    Ok(serde_json::Value::Array(outputs).to_string())
+}
+
+// Example invocations shown after the help for each subcommand
+// The auction public key is the generator (1, 2), so the matching private key is 1
+const ENCRYPT_EXAMPLES: &str = "Examples:\n  # Encrypt the message 1 with bid private key 3 and salt 1\n  ecies-cli encrypt 1 1 2 3 1\n\n  # Read the message from stdin and use a random salt\n  echo 1 | ecies-cli encrypt --message-stdin --random-salt 1 2 3";
+const DECRYPT_EXAMPLES: &str = "Examples:\n  # Decrypt the first encrypt example with private key 1 and salt 1\n  ecies-cli decrypt 0xc274fd06438d9d23dbbbfbf1c9016e6c38a8b0bf6c4d6b929cc788e2dad617aa \\\n      0x0769bf9ac56bea3ff40232bcb1b6bd159315d84715b8e679f2d355961915abf0 \\\n      0x2ab799bee0489429554fdb7c8d086475319e63b40b9c5b57cdf1ff3dd9fe2261 1 1";
+const DECRYPT_BLOB_EXAMPLES: &str = "Examples:\n  # Decrypt the output of the first encrypt example with private key 1 and salt 1\n  ecies-cli decrypt-blob $(ecies-cli encrypt 1 1 2 3 1) 1 1";
+const SALT_EXAMPLES: &str = "Examples:\n  # Salt for lot 1, the bidder, and an amount of 1e18\n  ecies-cli salt 1 0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496 1000000000000000000";
+const SALT_DERIVE_EXAMPLES: &str = "Examples:\n  # Salt for lot 1, the bidder, and an amount of 1e18\n  ecies-cli salt-derive 1 0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496 1000000000000000000";
+const SEAL_BID_EXAMPLES: &str = "Examples:\n  # Seal a bid of 1e18 on lot 1\n  ecies-cli seal-bid 1 0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496 1000000000000000000 1 2";
+const ENCRYPT_EMP_EXAMPLES: &str = "Examples:\n  # Encrypt an amount out of 500 for a bid of 1e18 on lot 1, with bid private key 3 and seed 7\n  ecies-cli encrypt-emp --seed 7 1 0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496 1000000000000000000 500 1 2 3";
+const DECRYPT_EMP_EXAMPLES: &str = "Examples:\n  # Decrypt the encrypt-emp example with private key 1\n  ecies-cli decrypt-emp 0xafe562c841d1023efc69db1db9659b9a5960e3fe10c9d2259736a37504fb4350 \\\n      0x0769bf9ac56bea3ff40232bcb1b6bd159315d84715b8e679f2d355961915abf0 \\\n      0x2ab799bee0489429554fdb7c8d086475319e63b40b9c5b57cdf1ff3dd9fe2261 \\\n      1 1 0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496 1000000000000000000";
+const KEYGEN_EXAMPLES: &str = "Examples:\n  # Generate one keypair\n  ecies-cli keygen\n\n  # Generate three keypairs as JSON lines\n  ecies-cli keygen --count 3";
+const ROUNDTRIP_EXAMPLES: &str = "Examples:\n  # Encrypt 1 with bid private key 3 and decrypt it with private key 1, using salt 1\n  ecies-cli roundtrip 1 1 2 3 1 1";
+const BATCH_ENCRYPT_EXAMPLES: &str = "Examples:\n  # Encrypt every record in bids.json\n  echo '[{\"message\": \"1\", \"bidPrivateKey\": \"3\", \"salt\": \"1\"}]' > bids.json\n  ecies-cli batch-encrypt bids.json 1 2";
+const ADDRESS_EXAMPLES: &str = "Examples:\n  # Address of the generator\n  ecies-cli address 1 2";
+const DERIVE_PUBKEY_EXAMPLES: &str =
+    "Examples:\n  # Public key for private key 2\n  ecies-cli derive-pubkey 2";
+const DECOMPRESS_EXAMPLES: &str = "Examples:\n  # Expand the compressed generator, whose y coordinate is even\n  ecies-cli decompress 0x020000000000000000000000000000000000000000000000000000000000000001";
+const DECODE_EXAMPLES: &str = "Examples:\n  # Split the output of encrypt into its fields\n  ecies-cli decode --json $(ecies-cli encrypt 1 1 2 3 1)";
+const CONVERT_EXAMPLES: &str = "Examples:\n  # Print 255 as hex\n  ecies-cli convert 255 --to hex\n\n  # Print a hex value as decimal\n  ecies-cli convert 0xff --to dec";
+const VERIFY_BID_EXAMPLES: &str = "Examples:\n  # Check an encrypted bid against its revealed bid private key 3\n  ecies-cli verify-bid $(ecies-cli encrypt 1 1 2 3 1) 3";
+const BENCH_EXAMPLES: &str = "Examples:\n  # Time 1000 encryptions\n  ecies-cli bench 1000";
+const DIFFTEST_EXAMPLES: &str =
+    "Examples:\n  # Print 10 vectors from a fixed seed\n  ecies-cli difftest 10 --seed 42";
+const VERIFY_VECTOR_EXAMPLES: &str = "Examples:\n  # Check the contract's encrypt vector for 2G, decrypting it with private key 2\n  echo '[{\"type\": \"ecies\", \"name\": \"encrypt 1 to 2G\", \"message\": \"1\", \"publicKeyX\": \"0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\", \"publicKeyY\": \"0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4\", \"bidPrivateKey\": \"3\", \"salt\": \"1\", \"ciphertext\": \"0xf96d7675ae04b89c9b5a9b0613d3530bb939186d05959efba9b3249a461abbc4\", \"bidPublicKeyX\": \"0x0769bf9ac56bea3ff40232bcb1b6bd159315d84715b8e679f2d355961915abf0\", \"bidPublicKeyY\": \"0x2ab799bee0489429554fdb7c8d086475319e63b40b9c5b57cdf1ff3dd9fe2261\", \"privateKey\": \"2\"}]' > vectors.json\n  ecies-cli verify-vector vectors.json";
+const VALIDATE_PUBKEY_EXAMPLES: &str = "Examples:\n  # Validate the generator\n  ecies-cli validate-pubkey 1 2\n\nExit codes:\n  5  the point is not on the curve\n  6  the point is not in the prime-order subgroup\n  7  the point is the identity";
+const VERSION_EXAMPLES: &str = "Examples:\n  # Print the build details\n  ecies-cli version";
+const REPL_EXAMPLES: &str = "Examples:\n  # Define the public key once and encrypt two messages to it\n  printf 'set x=1 y=2\\nencrypt 1 $x $y 3 1\\nencrypt 2 $x $y 3 1\\n' | ecies-cli repl";
+
 // CLI struct and subcommands
 #[derive(Parser, Debug)]
-#[clap(name = "ecies-cli")]
+#[clap(
+    name = "ecies-cli",
+    after_help = "Exit codes:\n  0  success\n  2  argument or parse error, or an input or output file that cannot be read or written\n  3  validation failure, such as an off-curve point or out-of-range scalar\n  4  crypto failure, such as an authentication tag or roundtrip mismatch\n  5  validate-pubkey: the point is not on the curve\n  6  validate-pubkey: the point is not in the prime-order subgroup\n  7  validate-pubkey: the point is the identity"
+)]
 struct Cli {
+    /// Suppress diagnostic output, so stdout holds only the result and stderr only what is needed to use it
+    ///
+    /// The result is always a single line. Commands that print several values, such as keygen, seal-bid,
+    /// decrypt-emp and verify-vector, print them as one JSON object, and any other multi-line result is
+    /// printed as a JSON array of its lines.
+    #[arg(long, global = true)]
+    quiet: bool,
+    /// Log the shared secret point, each symmetric key preimage, and each symmetric key to stderr
+    #[arg(long, global = true, conflicts_with = "quiet")]
+    verbose: bool,
+    /// Encoding for byte strings in the output, and for message, ciphertext and compressed point inputs
+    ///
+    /// Integers such as keys and salts are always accepted as decimal or 0x-prefixed hex.
+    #[arg(long, global = true, value_enum, default_value_t = Encoding::Hex)]
+    encoding: Encoding,
+    /// Write the result to this file instead of stdout, creating parent directories as needed
+    #[arg(long, global = true, value_name = "path")]
+    output: Option<PathBuf>,
+    /// Format for errors printed to stderr
+    ///
+    /// json prints one object, {"error": message, "kind": "parse|io|validation|crypto", "code": exit code}, for scripts to parse.
+    /// parse and io errors both exit with 2, and the kind tells them apart.
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    error_format: ErrorFormat,
+    /// Enable every safety check, including those that reject inputs the contract would accept or cannot decrypt
+    ///
+    /// These checks always run, with or without --strict: public key coordinates must be reduced modulo the base
+    /// field, public keys must be on the curve and in the prime-order subgroup, keys must be non-zero and reduced
+    /// modulo the group order, and the shared secret must not be the identity.
+    ///
+    /// --strict additionally rejects the generator (1, 2) as a public key, and 1 as a private key since it derives
+    /// the generator, because the contract's ECIES.isValid rejects the generator. It also rejects --zero-salt, and
+    /// a --single-block message that --truncate would cut. Each failed check exits with 3.
+    #[arg(long, global = true)]
+    strict: bool,
     #[clap(subcommand)]
     command: Commands,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
-    #[clap(name = "encrypt")]
+    /// Encrypt a message to an auction public key.
+    ///
+    /// Positionals supplied through an option (e.g. --message-stdin) are omitted, and the rest shift left.
+    #[clap(name = "encrypt", after_help = ENCRYPT_EXAMPLES)]
     Encrypt {
         #[arg(value_name = "message")]
-        message: BigUint,
+        message: Option<String>,
         #[arg(value_name = "public_key_x")]
-        public_key_x: BigUint,
+        public_key_x: Option<String>,
         #[arg(value_name = "public_key_y")]
-        public_key_y: BigUint,
+        public_key_y: Option<String>,
         #[arg(value_name = "bid_private_key")]
-        bid_private_key: BigUint,
+        bid_private_key: Option<String>,
         #[arg(value_name = "salt")]
-        salt: BigUint,
+        salt: Option<String>,
+        /// Read the message from stdin instead of the positional argument
+        #[arg(long)]
+        message_stdin: bool,
+        /// Use these raw 0x-prefixed hex bytes as the message instead of the positional argument, left-padded to 32 bytes
+        #[arg(long, value_name = "bytes", value_parser = parse_message_bytes, conflicts_with = "message_stdin")]
+        message_bytes: Option<Bytes>,
+        /// Require the message to fit in one 32-byte block, as the contract's ECIES.encrypt does
+        #[arg(long)]
+        single_block: bool,
+        /// With --single-block, keep the low-order 32 bytes of a longer message instead of erroring
+        #[arg(long, requires = "single_block")]
+        truncate: bool,
+        /// Pad the message to this many bytes before encrypting, as its exact bytes rather than a 32-byte block
+        ///
+        /// The padding is a 0x80 marker byte and then zeros, or zeros and then the marker with --pad-side left,
+        /// so `decrypt --unpad` with the same --pad-side strips it exactly. The message must be shorter than the target.
+        #[arg(long, value_name = "bytes", conflicts_with = "message_bytes")]
+        pad_to: Option<usize>,
+        /// Side of the message that --pad-to pads
+        #[arg(long, value_enum, default_value_t = PadSide::Right, requires = "pad_to")]
+        pad_side: PadSide,
+        /// Print the byte length of the encrypted blob for the message and options, without encrypting
+        ///
+        /// Only the message is required; any other positionals are ignored. With --recipients, the length is per recipient.
+        /// With --format json, the length is printed as {"byteLength": length}.
+        #[arg(long)]
+        estimate: bool,
+        /// Use a random salt instead of the positional argument, printing it to stderr
+        #[arg(long)]
+        random_salt: bool,
+        /// Use an all-zero salt instead of the positional argument, as some legacy test vectors do
+        #[arg(long, conflicts_with = "random_salt")]
+        zero_salt: bool,
+        /// Print the shared secret and symmetric key to stderr before the ciphertext
+        #[arg(long)]
+        debug: bool,
+        /// Output format for the encrypted bid
+        #[arg(long, value_enum, default_value_t = OutputFormat::Abi)]
+        format: OutputFormat,
+        /// Output the bid public key as a 33-byte compressed point: a 0x02 (even y) or 0x03 (odd y) prefix, then x as 32 big-endian bytes
+        #[arg(long)]
+        compressed: bool,
+        /// Append a 32-byte tag authenticating the ciphertext, keyed by keccak256(shared_secret_x || salt || "mac")
+        #[arg(long)]
+        authenticated: bool,
+        /// Encrypt to each public key in a JSON array of {"x", "y"} records with one fresh bid keypair, in place of public_key_x, public_key_y and bid_private_key
+        ///
+        /// The output is a JSON array with one object per recipient, keyed by its index in the "recipient" field.
+        #[arg(long, value_name = "path", conflicts_with_all = ["format", "debug"])]
+        recipients: Option<PathBuf>,
+        /// Use a 64-byte public key blob, x || y as the contract's bytes getters return it, in place of public_key_x and public_key_y
+        #[arg(long, value_name = "bytes", value_parser = parse_public_key_blob, conflicts_with = "recipients")]
+        pubkey_blob: Option<[u8; 64]>,
+        /// Fetch the public key of this EncryptedMarginalPrice lot over JSON-RPC, in place of public_key_x and public_key_y
+        ///
+        /// Requires --rpc and --auction-house. The key is read once per invocation and not cached.
+        #[arg(long, value_name = "n", value_parser = parse_u128, requires_all = ["rpc", "auction_house"], conflicts_with_all = ["recipients", "pubkey_blob"])]
+        lot_id: Option<u128>,
+        /// JSON-RPC url of the node to read the lot from, with --lot-id
+        #[arg(long, value_name = "url", requires = "lot_id")]
+        rpc: Option<String>,
+        /// Address of the AuctionHouse the lot was created in, with --lot-id
+        #[arg(long, value_name = "address", requires = "lot_id")]
+        auction_house: Option<Address>,
+        /// Also print a 16-byte fingerprint of the bid public key to stderr, keccak256(x || y) truncated to its first 16 bytes
+        ///
+        /// The fingerprint is a short index key for the bid, and is not cryptographically binding.
+        #[arg(long, conflicts_with = "recipients")]
+        fingerprint: bool,
+        /// Domain-separation tag added to each symmetric key preimage, as shared_secret_x || salt || len(domain) || domain || counter
+        ///
+        /// The encryptor and decryptor must use the same domain. The default empty domain matches the contract.
+        #[arg(long, value_name = "string", default_value = "")]
+        domain: String,
+        /// Symmetric layer for the message; aes-gcm authenticates arbitrary payloads but cannot be decrypted on-chain
+        #[arg(long, value_enum, default_value_t = Cipher::Xor)]
+        cipher: Cipher,
+        /// Group of the recipient public key, and so of the bid public key in the output
+        #[arg(long, value_enum, default_value_t = Group::G1)]
+        group: Group,
+        /// With --cipher aes-gcm, encrypt this file in chunks in place of the message, writing the ciphertext to --stream-to
+        ///
+        /// The file is never held in memory in full. Only the bid public key is printed, in the format of --compressed.
+        #[arg(long, value_name = "path", requires = "stream_to", conflicts_with_all = ["message_stdin", "message_bytes", "single_block", "estimate", "recipients", "format", "fingerprint"])]
+        stream_from: Option<PathBuf>,
+        /// File to write the streamed ciphertext to, with --stream-from
+        #[arg(long, value_name = "path", requires = "stream_from")]
+        stream_to: Option<PathBuf>,
+        /// Read the arguments from a JSON object keyed by camelCase argument name (message, publicKeyX, publicKeyY, bidPrivateKey, salt)
+        ///
+        /// Positionals on the command line fill the leading arguments and override the file.
+        #[arg(long, value_name = "path")]
+        params: Option<PathBuf>,
     },
+    /// Decrypt a ciphertext using the auction private key.
+    ///
+    /// Positionals supplied through an option (e.g. --private-key-stdin) are omitted, and the rest shift left.
+    #[clap(after_help = DECRYPT_EXAMPLES)]
     Decrypt {
         #[arg(value_name = "ciphertext")]
-        ciphertext: BigUint,
+        ciphertext: Option<String>,
         #[arg(value_name = "bid_public_key_x")]
-        bid_public_key_x: BigUint,
+        bid_public_key_x: Option<String>,
         #[arg(value_name = "bid_public_key_y")]
-        bid_public_key_y: BigUint,
+        bid_public_key_y: Option<String>,
         #[arg(value_name = "private_key")]
-        private_key: BigUint,
+        private_key: Option<String>,
         #[arg(value_name = "salt")]
-        salt: BigUint,
+        salt: Option<String>,
+        /// Read the private key from stdin instead of the positional argument
+        #[arg(long)]
+        private_key_stdin: bool,
+        /// Exit with a crypto failure unless the decrypted message equals this value, parsed as the message is in `encrypt`
+        #[arg(long, value_name = "message", value_parser = parse_message)]
+        assert_equals: Option<Bytes>,
+        /// Strip the padding added by `encrypt --pad-to` from the decrypted message
+        #[arg(long, conflicts_with = "assert_equals")]
+        unpad: bool,
+        /// Side of the message that `encrypt --pad-to` padded
+        #[arg(long, value_enum, default_value_t = PadSide::Right, requires = "unpad")]
+        pad_side: PadSide,
+        /// Use an all-zero salt instead of the positional argument, as some legacy test vectors do
+        #[arg(long)]
+        zero_salt: bool,
+        /// Accept the bid public key as a single 33-byte compressed point in place of bid_public_key_x and bid_public_key_y
+        #[arg(long)]
+        compressed: bool,
+        /// Treat the last 32 bytes of the ciphertext as the tag from `encrypt --authenticated`, and verify it before decrypting
+        #[arg(long)]
+        authenticated: bool,
+        /// Domain-separation tag added to each symmetric key preimage, as shared_secret_x || salt || len(domain) || domain || counter
+        ///
+        /// The encryptor and decryptor must use the same domain. The default empty domain matches the contract.
+        #[arg(long, value_name = "string", default_value = "")]
+        domain: String,
+        /// Symmetric layer for the message; aes-gcm authenticates arbitrary payloads but cannot be decrypted on-chain
+        #[arg(long, value_enum, default_value_t = Cipher::Xor)]
+        cipher: Cipher,
+        /// Group of the bid public key
+        #[arg(long, value_enum, default_value_t = Group::G1)]
+        group: Group,
+        /// With --cipher aes-gcm, decrypt this file from `encrypt --stream-from` in chunks in place of the ciphertext, writing the message to --stream-to
+        ///
+        /// The output file is removed if any chunk fails its tag check.
+        #[arg(
+            long,
+            value_name = "path",
+            requires = "stream_to",
+            conflicts_with = "assert_equals"
+        )]
+        stream_from: Option<PathBuf>,
+        /// File to write the streamed message to, with --stream-from
+        #[arg(long, value_name = "path", requires = "stream_from")]
+        stream_to: Option<PathBuf>,
+        /// Read the arguments from a JSON object keyed by camelCase argument name (ciphertext, bidPublicKeyX, bidPublicKeyY, privateKey, salt)
+        ///
+        /// With --compressed, the bid public key is read from bidPublicKey.
+        /// Positionals on the command line fill the leading arguments and override the file.
+        #[arg(long, value_name = "path")]
+        params: Option<PathBuf>,
+    },
+    /// Decrypt an abi-encoded encrypted bid, as printed by `encrypt` and stored on-chain, using the auction private key.
+    ///
+    /// The blob must be exactly 96 bytes: ciphertext (32) || bid public key x (32) || bid public key y (32).
+    #[clap(after_help = DECRYPT_BLOB_EXAMPLES)]
+    DecryptBlob {
+        #[arg(value_name = "blob")]
+        blob: String,
+        #[arg(value_name = "private_key", value_parser = parse_biguint)]
+        private_key: BigUint,
+        #[arg(value_name = "salt", value_parser = parse_bytes32)]
+        salt: [u8; 32],
     },
+    #[clap(after_help = SALT_EXAMPLES)]
     Salt {
-        #[arg(value_name = "lot_id")]
-        lot_id: BigUint,
+        #[arg(value_name = "lot_id", value_parser = parse_bytes32)]
+        lot_id: [u8; 32],
         #[arg(value_name = "bidder_address")]
         bidder_address: Address,
-        #[arg(value_name = "amount")]
+        #[arg(value_name = "amount", value_parser = parse_bytes32)]
+        amount: [u8; 32],
+    },
+    /// Derive a salt as keccak256(abi.encode(lot_id, bidder, amount)), with each field padded to a 32-byte word.
+    ///
+    /// The output can be passed directly as the salt to `encrypt`. This is not the salt EncryptedMarginalPrice bids use:
+    /// EMP.sol derives it with abi.encodePacked, as the packed uint96 || address || uint96 preimage, which `salt` computes.
+    #[clap(after_help = SALT_DERIVE_EXAMPLES)]
+    SaltDerive {
+        #[arg(value_name = "lot_id", value_parser = parse_u256)]
+        lot_id: U256,
+        #[arg(value_name = "bidder")]
+        bidder: Address,
+        #[arg(value_name = "amount", value_parser = parse_u256)]
+        amount: U256,
+    },
+    /// Seal a bid: derive its salt as `salt` does, then encrypt the amount with a fresh random bid private key.
+    ///
+    /// Prints the abi-encoded encrypted bid, then the bid private key, which is needed to reveal the bid later.
+    #[clap(after_help = SEAL_BID_EXAMPLES)]
+    SealBid {
+        #[arg(value_name = "lot_id", value_parser = parse_biguint)]
+        lot_id: BigUint,
+        #[arg(value_name = "bidder")]
+        bidder: Address,
+        #[arg(value_name = "amount", value_parser = parse_biguint)]
+        amount: BigUint,
+        #[arg(value_name = "public_key_x", value_parser = parse_biguint)]
+        public_key_x: BigUint,
+        #[arg(value_name = "public_key_y", value_parser = parse_biguint)]
+        public_key_y: BigUint,
+    },
+    /// Encrypt an EncryptedMarginalPrice bid, packing the amount out with a seed as EMP.decryptBid expects.
+    ///
+    /// The message is seed (16 bytes) || uint128(amount_out - seed), and the salt is derived from the bid as `salt` does.
+    /// The output is the abi-encoded (uint256 encryptedAmountOut, Point bidPubKey) for the bid's auction data.
+    /// The referrer is not part of the encrypted message, since the module stores it in the clear.
+    #[clap(after_help = ENCRYPT_EMP_EXAMPLES)]
+    EncryptEmp {
+        #[arg(value_name = "lot_id", value_parser = parse_biguint)]
+        lot_id: BigUint,
+        #[arg(value_name = "bidder")]
+        bidder: Address,
+        #[arg(value_name = "amount", value_parser = parse_biguint)]
+        amount: BigUint,
+        #[arg(value_name = "amount_out", value_parser = parse_u128)]
+        amount_out: u128,
+        #[arg(value_name = "public_key_x", value_parser = parse_biguint)]
+        public_key_x: BigUint,
+        #[arg(value_name = "public_key_y", value_parser = parse_biguint)]
+        public_key_y: BigUint,
+        #[arg(value_name = "bid_private_key", value_parser = parse_biguint)]
+        bid_private_key: BigUint,
+        /// Use this 128-bit seed instead of a random one, which is printed to stderr
+        #[arg(long, value_parser = parse_u128)]
+        seed: Option<u128>,
+    },
+    /// Decrypt an EncryptedMarginalPrice bid and unpack its seed, masked value and amount out.
+    ///
+    /// The salt is derived from the bid as `salt` does.
+    #[clap(after_help = DECRYPT_EMP_EXAMPLES)]
+    DecryptEmp {
+        #[arg(value_name = "ciphertext", value_parser = parse_bytes32)]
+        ciphertext: [u8; 32],
+        #[arg(value_name = "bid_public_key_x", value_parser = parse_biguint)]
+        bid_public_key_x: BigUint,
+        #[arg(value_name = "bid_public_key_y", value_parser = parse_biguint)]
+        bid_public_key_y: BigUint,
+        #[arg(value_name = "private_key", value_parser = parse_biguint)]
+        private_key: BigUint,
+        #[arg(value_name = "lot_id", value_parser = parse_biguint)]
+        lot_id: BigUint,
+        #[arg(value_name = "bidder")]
+        bidder: Address,
+        #[arg(value_name = "amount", value_parser = parse_biguint)]
         amount: BigUint,
     },
+    /// Generate a fresh auction keypair.
+    ///
+    /// The private key is printed in the clear and is intended for test use only.
+    #[clap(after_help = KEYGEN_EXAMPLES)]
+    Keygen {
+        /// Generate this many distinct keypairs, printed as one JSON object per line
+        #[arg(long, value_name = "n")]
+        count: Option<usize>,
+    },
+    /// Encrypt a message and decrypt it again, checking the recovered message matches.
+    #[clap(after_help = ROUNDTRIP_EXAMPLES)]
+    Roundtrip {
+        #[arg(value_name = "message")]
+        message: String,
+        #[arg(value_name = "public_key_x", value_parser = parse_biguint)]
+        public_key_x: BigUint,
+        #[arg(value_name = "public_key_y", value_parser = parse_biguint)]
+        public_key_y: BigUint,
+        #[arg(value_name = "bid_private_key", value_parser = parse_biguint)]
+        bid_private_key: BigUint,
+        #[arg(value_name = "private_key", value_parser = parse_biguint)]
+        private_key: BigUint,
+        #[arg(value_name = "salt", value_parser = parse_bytes32)]
+        salt: [u8; 32],
+    },
+    /// Encrypt a batch of messages to an auction public key.
+    ///
+    /// The input file is a JSON array of {"message", "bidPrivateKey", "salt"} records.
+    /// One encrypted bid is printed per line, in the same format as `encrypt`.
+    #[clap(after_help = BATCH_ENCRYPT_EXAMPLES)]
+    BatchEncrypt {
+        #[arg(value_name = "input")]
+        input: PathBuf,
+        #[arg(value_name = "public_key_x", value_parser = parse_biguint)]
+        public_key_x: BigUint,
+        #[arg(value_name = "public_key_y", value_parser = parse_biguint)]
+        public_key_y: BigUint,
+        /// Output format for each encrypted bid
+        #[arg(long, value_enum, default_value_t = OutputFormat::Abi)]
+        format: OutputFormat,
+        /// Number of threads to encrypt the records with, instead of one per CPU
+        #[arg(long, value_name = "n")]
+        jobs: Option<NonZeroUsize>,
+    },
+    /// Derive the checksummed Ethereum-style address of a public key.
+    #[clap(after_help = ADDRESS_EXAMPLES)]
+    Address {
+        #[arg(value_name = "public_key_x", value_parser = parse_biguint)]
+        public_key_x: BigUint,
+        #[arg(value_name = "public_key_y", value_parser = parse_biguint)]
+        public_key_y: BigUint,
+    },
+    /// Derive the public key corresponding to a private key.
+    #[clap(after_help = DERIVE_PUBKEY_EXAMPLES)]
+    DerivePubkey {
+        #[arg(value_name = "private_key", value_parser = parse_biguint)]
+        private_key: BigUint,
+    },
+    /// Expand a 33-byte compressed G1 point, as output by `encrypt --compressed`, into its coordinates.
+    ///
+    /// Prints x and y, one per line. Errors if the prefix is invalid or no y coordinate exists for x.
+    #[clap(after_help = DECOMPRESS_EXAMPLES)]
+    Decompress {
+        #[arg(value_name = "compressed")]
+        compressed: String,
+    },
+    /// Split an abi-encoded encrypted bid into its ciphertext and bid public key without decrypting it.
+    ///
+    /// The blob must be exactly 96 bytes: ciphertext (32) || bid public key x (32) || bid public key y (32).
+    #[clap(after_help = DECODE_EXAMPLES)]
+    Decode {
+        #[arg(value_name = "blob")]
+        blob: String,
+        /// Print the fields as a JSON object, as `encrypt --format json` does
+        #[arg(long)]
+        json: bool,
+    },
+    /// Convert an integer of up to 32 bytes between decimal and 0x-prefixed hex.
+    #[clap(after_help = CONVERT_EXAMPLES)]
+    Convert {
+        #[arg(value_name = "value", value_parser = parse_u256)]
+        value: U256,
+        /// Radix to print the value in
+        #[arg(long, value_enum)]
+        to: Radix,
+    },
+    /// Check that the bid public key in an abi-encoded encrypted bid is derived from a revealed bid private key.
+    ///
+    /// This is the check the AuctionHouse performs when a bid is decrypted. Exits non-zero on a mismatch.
+    #[clap(after_help = VERIFY_BID_EXAMPLES)]
+    VerifyBid {
+        #[arg(value_name = "blob")]
+        blob: String,
+        #[arg(value_name = "bid_private_key", value_parser = parse_biguint)]
+        bid_private_key: BigUint,
+    },
+    /// Measure encryption throughput with a fixed key, reporting the timings to stderr.
+    #[clap(after_help = BENCH_EXAMPLES)]
+    Bench {
+        #[arg(value_name = "iterations")]
+        iterations: usize,
+    },
+    /// Print random encryption vectors for replaying against the contract, one per line.
+    ///
+    /// Each line is message,public_key_x,public_key_y,bid_private_key,salt,ciphertext,bid_public_key_x,bid_public_key_y
+    /// as 0x-prefixed 32-byte hex, in the order ECIES.encrypt takes its arguments and returns its results.
+    /// The seed is printed to stderr, so a failing run can be reproduced with --seed.
+    /// test/lib/ECIES/difftest.t.sol replays a vector per fuzzed seed through ECIES.encrypt and ECIES.decrypt.
+    #[clap(after_help = DIFFTEST_EXAMPLES)]
+    Difftest {
+        #[arg(value_name = "iterations")]
+        iterations: usize,
+        /// Seed for the random inputs, instead of a random seed
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Check every case in a JSON test-vector file against this implementation, printing a pass/fail report.
+    ///
+    /// The file is a JSON array of cases tagged by "type". An "ecies" case has message, publicKeyX, publicKeyY,
+    /// bidPrivateKey, salt, an optional domain, and the expected ciphertext, bidPublicKeyX and bidPublicKeyY.
+    /// It may also have an optional name and privateKey; with a private key, the expected ciphertext is also
+    /// decrypted and checked against the message. "rsa" cases are reported as skipped. Exits with a crypto
+    /// failure if any case fails.
+    #[clap(after_help = VERIFY_VECTOR_EXAMPLES)]
+    VerifyVector {
+        #[arg(value_name = "file")]
+        file: PathBuf,
+    },
+    /// Check that an auction public key is a usable bn254 G1 point, printing a pass/fail report.
+    ///
+    /// The point must not be the identity (0, 0), must be on the curve, and must be in the prime-order subgroup.
+    /// These checks always run, here and wherever a public key is accepted; see --strict for the checks it adds elsewhere.
+    /// Each failed check exits with its own code, listed below.
+    #[clap(after_help = VALIDATE_PUBKEY_EXAMPLES)]
+    ValidatePubkey {
+        #[arg(value_name = "x", value_parser = parse_biguint)]
+        x: BigUint,
+        #[arg(value_name = "y", value_parser = parse_biguint)]
+        y: BigUint,
+    },
+    /// Print the build and the cryptographic parameters it uses, for bug reports.
+    #[clap(after_help = VERSION_EXAMPLES)]
+    Version,
+    /// Read commands from stdin, one per line, and run each of them until stdin is closed.
+    ///
+    /// Each line is a command as it would be given to ecies-cli, e.g. "decrypt $ct $bx $by 2 1".
+    /// "set name=value" defines a variable, which $name then substitutes as a whole argument, and a bare "set" lists them.
+    /// Arguments are split on whitespace, without quoting. Commands cannot read values from stdin, and --encoding can only be given to repl itself.
+    #[clap(after_help = REPL_EXAMPLES)]
+    Repl,
+    /// Print a shell completion script to stdout.
+    ///
+    /// Install paths for each shell:
+    ///
+    ///   bash:        ecies-cli completions bash > ~/.local/share/bash-completion/completions/ecies-cli
+    ///
+    ///   zsh:         ecies-cli completions zsh > ~/.zfunc/_ecies-cli (with ~/.zfunc in $fpath before compinit)
+    ///
+    ///   fish:        ecies-cli completions fish > ~/.config/fish/completions/ecies-cli.fish
+    ///
+    ///   powershell:  ecies-cli completions powershell >> $PROFILE
+    Completions {
+        #[arg(value_name = "shell")]
+        shell: Shell,
+    },
 }
 
-fn main() -> Result<()> {
-    let args = Cli::parse();
+fn main() -> ExitCode {
+    // Log the library's crypto spans to stderr at the level set in RUST_LOG, e.g. RUST_LOG=axis_ecies=debug
+    // Nothing is logged when RUST_LOG is unset
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+
+    // Read the error format from the raw arguments, so errors in parsing them are reported in it too
+    let error_format = ErrorFormat::from_args(std::env::args());
+    match try_main() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => err.report(error_format),
+    }
+}
+
+fn try_main() -> Result<()> {
+    let args = Cli::try_parse()?;
+    encoding::set_encoding(args.encoding);
+    if let Commands::Repl = args.command {
+        return repl::repl(&args);
+    }
+    let (output, quiet) = (args.output.clone(), args.quiet);
+    let results = run(args, &mut OsRng)?;
+    write_results(&results, output.as_deref(), quiet)
+}
+
+// Runs a parsed command, drawing keys, salts and seeds from the given CSPRNG
+// The binary always uses OsRng, and tests can pass a seeded RNG to make the output reproducible
+fn run(args: Cli, rng: &mut (impl RngCore + CryptoRng)) -> Result<Vec<String>> {
+    let quiet = args.quiet;
+    let verbose = args.verbose;
+    STRICT.store(args.strict, Ordering::Relaxed);
+
+    // Collect the result lines, so they can be written to stdout or the output file at the end
+    let mut results = Vec::new();
+
     match args.command {
         Commands::Encrypt {
             message,
@@ -74,61 +1402,298 @@ fn main() -> Result<()> {
             public_key_y,
             bid_private_key,
             salt,
+            message_stdin,
+            message_bytes,
+            single_block,
+            truncate,
+            pad_to,
+            pad_side,
+            estimate,
+            random_salt,
+            zero_salt,
+            debug,
+            format,
+            compressed,
+            authenticated,
+            recipients,
+            pubkey_blob,
+            lot_id,
+            rpc,
+            auction_house,
+            fingerprint,
+            domain,
+            cipher,
+            group,
+            stream_from,
+            stream_to,
+            params,
         } => {
-            // Convert message and salt to U256 types
-            let message = U256::from_big_endian(&message.to_bytes_be());
-            let salt = U256::from_big_endian(&salt.to_bytes_be());
+            if stream_from.is_some() && cipher != Cipher::AesGcm {
+                return Err(Error::parse("--stream-from requires --cipher aes-gcm"));
+            }
+            if cipher == Cipher::AesGcm {
+                // The GCM key and nonce are both derived from the bid private key and salt, so a caller-chosen
+                // salt could repeat a nonce under the same key, which breaks both confidentiality and the tag
+                if !random_salt && !estimate {
+                    return Err(Error::parse(
+                        "--cipher aes-gcm requires --random-salt, since reusing a bid private key and salt would reuse the GCM nonce",
+                    ));
+                }
+                check_unsupported_options(
+                    "--cipher aes-gcm",
+                    &[
+                        ("--authenticated", authenticated),
+                        ("--recipients", recipients.is_some()),
+                        ("--group g2", group == Group::G2),
+                        ("--verbose", verbose),
+                    ],
+                )?;
+            }
+            if group == Group::G2 {
+                check_unsupported_options(
+                    "--group g2",
+                    &[
+                        ("--debug", debug),
+                        ("--compressed", compressed),
+                        ("--authenticated", authenticated),
+                        ("--recipients", recipients.is_some()),
+                        ("--pubkey-blob", pubkey_blob.is_some()),
+                        ("--lot-id", lot_id.is_some()),
+                        ("--fingerprint", fingerprint),
+                        ("--domain", !domain.is_empty()),
+                        ("--verbose", verbose),
+                    ],
+                )?;
+            }
 
-            // Convert public key coordinates and bid private key to ark-bn254 types
-            let x = BaseField::from(public_key_x);
-            let y = BaseField::from(public_key_y);
-            let bid_private_key = ScalarField::from(bid_private_key);
+            // Resolve the positional arguments
+            // The message is read from stdin if requested, so it does not leak via process arguments
+            let mut positionals =
+                Positionals::new([message, public_key_x, public_key_y, bid_private_key, salt])
+                    .with_params(params.as_deref())?;
+            // A streamed message is read from its file while encrypting instead, so it is never held in memory
+            // A message to pad is kept as its exact bytes rather than as a 32-byte block
+            let message = match (&stream_from, message_bytes, message_stdin, pad_to) {
+                (Some(_), ..) => None,
+                (None, Some(message), ..) => Some(message),
+                (None, None, true, None) => Some(read_stdin("message", parse_message)?),
+                (None, None, false, None) => Some(positionals.next("message", parse_message)?),
+                (None, None, stdin, Some(length)) => {
+                    let message = match stdin {
+                        true => read_stdin("message", parse_unpadded_message)?,
+                        false => positionals.next("message", parse_unpadded_message)?,
+                    };
+                    Some(Bytes::from(pad_message(&message, length, pad_side)?))
+                }
+            };
+            let message = match (message, single_block) {
+                (Some(message), true) => Some(single_block_message(message, truncate)?),
+                (message, _) => message,
+            };
 
-            // Construct public key from coordinates
-            // Will revert if the point is not on the curve
-            let public_key = G1::new(x, y);
+            // Report the blob length without encrypting if requested
+            // The ciphertext has the same length as the message plus any GCM tag, followed by the bid public key and the optional tag
+            if estimate {
+                let message = message.expect("the message is resolved unless streaming");
+                let bid_public_key_length = match (group, compressed) {
+                    (Group::G2, _) => 128,
+                    (Group::G1, true) => 33,
+                    (Group::G1, false) => 64,
+                };
+                let tag_length = match (cipher, authenticated) {
+                    (Cipher::AesGcm, _) => axis_ecies::gcm::TAG_LENGTH,
+                    (Cipher::Xor, true) => 32,
+                    (Cipher::Xor, false) => 0,
+                };
+                let length = message.len() + bid_public_key_length + tag_length;
+                return Ok(vec![match format {
+                    OutputFormat::Json => serde_json::json!({ "byteLength": length }).to_string(),
+                    _ => length.to_string(),
+                }]);
+            }
+            // Construct public key from coordinates in the selected group, by splitting the blob into its halves, or from the chain
+            // Will error if the point is not on the curve
+            let keys = match (&recipients, group) {
+                (Some(_), _) => None,
+                (None, Group::G1) => {
+                    let (x, y) = match (pubkey_blob, lot_id, rpc, auction_house) {
+                        (Some(blob), ..) => (
+                            BigUint::from_bytes_be(&blob[..32]),
+                            BigUint::from_bytes_be(&blob[32..]),
+                        ),
+                        (None, Some(lot_id), Some(rpc), Some(auction_house)) => {
+                            rpc::fetch_auction_public_key(&rpc, auction_house, lot_id)?
+                        }
+                        _ => (
+                            positionals.next("public_key_x", parse_biguint)?,
+                            positionals.next("public_key_y", parse_biguint)?,
+                        ),
+                    };
+                    Some((
+                        PublicKey::G1(g1_from_coordinates(x, y, "public key")?),
+                        positionals.next("bid_private_key", parse_biguint)?,
+                    ))
+                }
+                (None, Group::G2) => Some((
+                    PublicKey::G2(g2_from_coordinates(
+                        positionals.next("public_key_x", parse_fq2)?,
+                        positionals.next("public_key_y", parse_fq2)?,
+                        "public key",
+                    )?),
+                    positionals.next("bid_private_key", parse_biguint)?,
+                )),
+            };
+            let salt = match (random_salt, zero_salt) {
+                (true, _) => None,
+                (false, true) => Some(zero_salt_value(quiet)?),
+                (false, false) => Some(positionals.next("salt", parse_bytes32)?),
+            };
+            positionals.finish()?;
 
-            // Encrypt the message
+            // Sample a random salt if requested
+            // The random salt is printed to stderr so the decryptor can recover it, even with --quiet
+            let salt = match salt {
+                Some(salt) => salt,
+                None => {
+                    let salt = axis_ecies::generate_salt_with_rng(rng);
+                    eprintln!("salt: {}", bytes_to_string(&salt));
+                    salt
+                }
+            };
 
-            //  Calculate the bid public key using the bid private key
-            let bid_public_key = (G1::generator() * bid_private_key).into_affine();
+            // Stream the file through AES-GCM instead if requested
+            // Only the bid public key is printed, since the ciphertext is written to the output file
+            if let (Some(input), Some(output)) = (&stream_from, &stream_to) {
+                let (public_key, bid_private_key) =
+                    keys.expect("keys are resolved without --recipients");
+                let PublicKey::G1(public_key) = public_key else {
+                    unreachable!("--cipher aes-gcm rejects --group g2");
+                };
+                let bid_private_key = scalar_from_biguint(bid_private_key, "bid private key")?;
 
-            //  Calculate a shared secret public key using the bid public key and the auction public key
-            let shared_secret_public_key = (public_key * bid_private_key).into_affine();
+                let bid_public_key = stream_file(input, output, quiet, |reader, writer| {
+                    axis_ecies::gcm::encrypt_stream(
+                        reader,
+                        writer,
+                        public_key,
+                        bid_private_key,
+                        &salt,
+                        domain.as_bytes(),
+                    )
+                })?;
+                // With no ciphertext, the abi format is the bid public key alone
+                let encrypted = axis_ecies::EncryptedBid {
+                    ciphertext: Vec::new(),
+                    bid_public_key,
+                };
+                return Ok(vec![format_encrypted_bid(
+                    encrypted,
+                    OutputFormat::Abi,
+                    compressed,
+                    None,
+                )]);
+            }
+            let message = message.expect("the message is resolved unless streaming");
 
-            //  Calculate the symmetric key by taking the keccak256 hash of the x coordinate of shared secret public key and the salt
-            let mut shared_secret_bytes = [0u8; 32];
-            U256::from_big_endian(&BigUint::from(shared_secret_public_key.x).to_bytes_be())
-                .to_big_endian(&mut shared_secret_bytes);
-            let mut salt_bytes = [0u8; 32];
-            salt.to_big_endian(&mut salt_bytes);
-            let symmetric_key = ethers::utils::keccak256(
-                [shared_secret_bytes.to_vec(), salt_bytes.to_vec()].concat(),
-            );
+            // Encrypt to each recipient instead if requested
+            if let Some(recipients) = recipients {
+                let output = encrypt_to_recipients(
+                    &recipients,
+                    &message,
+                    &salt,
+                    domain.as_bytes(),
+                    compressed,
+                    authenticated,
+                    rng,
+                )?;
+                return Ok(vec![output]);
+            }
+            let (public_key, bid_private_key) =
+                keys.expect("keys are resolved without --recipients");
 
-            //  Encrypt the message by XORing the message with the symmetric key
-            let mut message_bytes = [0u8; 32];
-            message.to_big_endian(&mut message_bytes);
-            let ciphertext = message_bytes
-                .iter()
-                .zip(symmetric_key.iter())
-                .map(|(a, b)| a ^ b)
-                .collect::<Vec<u8>>();
+            // Convert bid private key to ark-bn254 type
+            // Will error if it is not below the bn254 group order
+            let bid_private_key = scalar_from_biguint(bid_private_key, "bid private key")?;
 
-            // Combine the ciphertext and the bid public key into a hex-encoded string to return (abi-encoded)
-            let mut x_bytes = [0u8; 32];
-            U256::from_big_endian(&BigUint::from(bid_public_key.x).to_bytes_be())
-                .to_big_endian(&mut x_bytes);
+            // Encrypt to a G2 public key, which supports no other options
+            let public_key = match public_key {
+                PublicKey::G1(public_key) => public_key,
+                PublicKey::G2(public_key) => {
+                    let encrypted =
+                        axis_ecies::g2::encrypt(&message, public_key, bid_private_key, &salt)?;
+                    return Ok(vec![format_encrypted_g2_bid(encrypted, format)]);
+                }
+            };
 
-            let mut y_bytes = [0u8; 32];
-            U256::from_big_endian(&BigUint::from(bid_public_key.y).to_bytes_be())
-                .to_big_endian(&mut y_bytes);
+            // Print the intermediate values to stderr so stdout stays machine-parseable
+            if debug && !quiet {
+                let shared_secret = axis_ecies::derive_shared_secret(public_key, bid_private_key)?;
+                eprintln!("shared secret x: {}", bytes_to_string(&shared_secret));
+                eprintln!(
+                    "symmetric key: {}",
+                    bytes_to_string(&axis_ecies::derive_symmetric_key(
+                        &shared_secret,
+                        &salt,
+                        domain.as_bytes(),
+                        0
+                    ))
+                );
+            }
+            if verbose {
+                log_key_schedule(
+                    public_key,
+                    bid_private_key,
+                    &salt,
+                    domain.as_bytes(),
+                    message.len(),
+                )?;
+            }
 
-            let output =
-                bytes_to_string(&[ciphertext, x_bytes.to_vec(), y_bytes.to_vec()].concat());
+            // Encrypt the message, calculating the authentication tag if requested
+            // The AES-GCM tag is part of the ciphertext instead
+            let (encrypted, tag) = match (cipher, authenticated) {
+                (Cipher::AesGcm, _) => (
+                    axis_ecies::gcm::encrypt(
+                        &message,
+                        public_key,
+                        bid_private_key,
+                        &salt,
+                        domain.as_bytes(),
+                    )?,
+                    None,
+                ),
+                (Cipher::Xor, true) => {
+                    let (encrypted, tag) = axis_ecies::encrypt_authenticated(
+                        &message,
+                        public_key,
+                        bid_private_key,
+                        &salt,
+                        domain.as_bytes(),
+                    )?;
+                    (encrypted, Some(tag))
+                }
+                (Cipher::Xor, false) => (
+                    axis_ecies::encrypt_with_domain(
+                        &message,
+                        public_key,
+                        bid_private_key,
+                        &salt,
+                        domain.as_bytes(),
+                    )?,
+                    None,
+                ),
+            };
+
+            // Print the fingerprint to stderr so the main output is unchanged, even with --quiet since it was requested
+            if fingerprint {
+                eprintln!(
+                    "fingerprint: {}",
+                    bytes_to_string(&bid_public_key_fingerprint(encrypted.bid_public_key))
+                );
+            }
 
             // Print output to command line
-            println!("{}", output);
+            results.push(format_encrypted_bid(encrypted, format, compressed, tag));
         }
         Commands::Decrypt {
             ciphertext,
@@ -136,82 +1701,1020 @@ fn main() -> Result<()> {
             bid_public_key_y,
             private_key,
             salt,
+            private_key_stdin,
+            assert_equals,
+            unpad,
+            pad_side,
+            zero_salt,
+            compressed,
+            authenticated,
+            domain,
+            cipher,
+            group,
+            stream_from,
+            stream_to,
+            params,
         } => {
-            // Convert ciphertext and salt to U256
-            let ciphertext = U256::from_big_endian(&ciphertext.to_bytes_be());
-            let salt = U256::from_big_endian(&salt.to_bytes_be());
-
-            // Convert bid public key coordinates and private key to ark-bn254 types
-            let x = BaseField::from(bid_public_key_x);
-            let y = BaseField::from(bid_public_key_y);
-            let private_key = ScalarField::from(private_key);
-
-            // Construct bid public key from coordinates
-            // Will revert if the point is not on the curve
-            let bid_public_key = G1::new(x, y);
-
-            // Calculate the shared secret public key using the bid public key and the private key
-            let shared_secret_public_key = (bid_public_key * private_key).into_affine();
-
-            // Calculate the symmetric key by taking the keccak256 hash of the x coordinate of shared secret public key and the salt
-            let mut shared_secret_bytes = [0u8; 32];
-            U256::from_big_endian(&BigUint::from(shared_secret_public_key.x).to_bytes_be())
-                .to_big_endian(&mut shared_secret_bytes);
-            let mut salt_bytes = [0u8; 32];
-            salt.to_big_endian(&mut salt_bytes);
-            let symmetric_key = ethers::utils::keccak256(
-                [shared_secret_bytes.to_vec(), salt_bytes.to_vec()].concat(),
-            );
+            if stream_from.is_some() && cipher != Cipher::AesGcm {
+                return Err(Error::parse("--stream-from requires --cipher aes-gcm"));
+            }
+            if cipher == Cipher::AesGcm {
+                check_unsupported_options(
+                    "--cipher aes-gcm",
+                    &[
+                        ("--authenticated", authenticated),
+                        ("--group g2", group == Group::G2),
+                        ("--verbose", verbose),
+                    ],
+                )?;
+            }
+            if group == Group::G2 {
+                check_unsupported_options(
+                    "--group g2",
+                    &[
+                        ("--compressed", compressed),
+                        ("--authenticated", authenticated),
+                        ("--domain", !domain.is_empty()),
+                        ("--verbose", verbose),
+                    ],
+                )?;
+            }
+
+            // Resolve the positional arguments
+            // The private key is read from stdin if requested, so it does not leak via process arguments
+            let mut positionals = Positionals::new([
+                ciphertext,
+                bid_public_key_x,
+                bid_public_key_y,
+                private_key,
+                salt,
+            ])
+            .with_params(params.as_deref())?;
+            let ciphertext = match stream_from {
+                Some(_) => None,
+                None => Some(positionals.next("ciphertext", parse_message)?),
+            };
+
+            // Construct bid public key from coordinates in the selected group, or from the compressed point
+            // Will error if the point is not on the curve
+            let bid_public_key = match (compressed, group) {
+                (true, _) => {
+                    let bid_public_key =
+                        positionals.next("bid_public_key", parse_compressed_point)?;
+                    PublicKey::G1(axis_ecies::decompress(&bid_public_key).map_err(|_| {
+                        Error::validation("compressed bid public key is not a valid bn254 G1 point")
+                    })?)
+                }
+                (false, Group::G1) => {
+                    let bid_public_key_x = positionals.next("bid_public_key_x", parse_biguint)?;
+                    let bid_public_key_y = positionals.next("bid_public_key_y", parse_biguint)?;
+                    PublicKey::G1(g1_from_coordinates(
+                        bid_public_key_x,
+                        bid_public_key_y,
+                        "bid public key",
+                    )?)
+                }
+                (false, Group::G2) => {
+                    let bid_public_key_x = positionals.next("bid_public_key_x", parse_fq2)?;
+                    let bid_public_key_y = positionals.next("bid_public_key_y", parse_fq2)?;
+                    PublicKey::G2(g2_from_coordinates(
+                        bid_public_key_x,
+                        bid_public_key_y,
+                        "bid public key",
+                    )?)
+                }
+            };
+
+            let private_key = match private_key_stdin {
+                true => read_stdin("private key", parse_biguint)?,
+                false => positionals.next("private_key", parse_biguint)?,
+            };
+            let salt = match zero_salt {
+                true => zero_salt_value(quiet)?,
+                false => positionals.next("salt", parse_bytes32)?,
+            };
+            positionals.finish()?;
+
+            // Convert private key to ark-bn254 type
+            // Will error if it is not below the bn254 group order
+            let private_key = scalar_from_biguint(private_key, "private key")?;
 
-            // Decrypt the message by XORing the ciphertext with the symmetric key
-            let mut ciphertext_bytes = [0u8; 32];
-            ciphertext.to_big_endian(&mut ciphertext_bytes);
+            // Stream the file through AES-GCM instead if requested, writing the message to the output file
+            if let (Some(input), Some(output)) = (&stream_from, &stream_to) {
+                let PublicKey::G1(bid_public_key) = bid_public_key else {
+                    unreachable!("--cipher aes-gcm rejects --group g2");
+                };
+                stream_file(input, output, quiet, |reader, writer| {
+                    axis_ecies::gcm::decrypt_stream(
+                        reader,
+                        writer,
+                        bid_public_key,
+                        private_key,
+                        &salt,
+                        domain.as_bytes(),
+                    )
+                })?;
+                return Ok(results);
+            }
+            let ciphertext = ciphertext.expect("the ciphertext is resolved unless streaming");
 
-            let message = ciphertext_bytes
-                .iter()
-                .zip(symmetric_key.iter())
-                .map(|(a, b)| a ^ b)
-                .collect::<Vec<u8>>();
+            // Decrypt with a G2 bid public key, which supports no other options
+            let bid_public_key = match bid_public_key {
+                PublicKey::G1(bid_public_key) => bid_public_key,
+                PublicKey::G2(bid_public_key) => {
+                    let message =
+                        axis_ecies::g2::decrypt(&ciphertext, bid_public_key, private_key, &salt)?;
+                    check_expected_message(&message, assert_equals.as_deref())?;
+                    let message = match unpad {
+                        true => unpad_message(&message, pad_side)?,
+                        false => message,
+                    };
+                    return Ok(vec![bytes_to_string(&message)]);
+                }
+            };
 
-            // Convert the message to a hex-encoded string (abi-encoded since it is one slot)
+            // Print the intermediate values to stderr so stdout stays machine-parseable
+            if verbose {
+                let length = match authenticated {
+                    true => ciphertext.len().saturating_sub(32),
+                    false => ciphertext.len(),
+                };
+                log_key_schedule(
+                    bid_public_key,
+                    private_key,
+                    &salt,
+                    domain.as_bytes(),
+                    length,
+                )?;
+            }
+
+            // Decrypt the message, first verifying the trailing authentication tag if requested
+            let message = match (cipher, authenticated) {
+                (Cipher::AesGcm, _) => axis_ecies::gcm::decrypt(
+                    &ciphertext,
+                    bid_public_key,
+                    private_key,
+                    &salt,
+                    domain.as_bytes(),
+                )?,
+                (Cipher::Xor, true) => {
+                    if ciphertext.len() <= 32 {
+                        return Err(Error::validation(
+                            "ciphertext is too short to contain a 32-byte authentication tag",
+                        ));
+                    }
+                    let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - 32);
+                    axis_ecies::decrypt_authenticated(
+                        ciphertext,
+                        tag.try_into().unwrap(),
+                        bid_public_key,
+                        private_key,
+                        &salt,
+                        domain.as_bytes(),
+                    )?
+                }
+                (Cipher::Xor, false) => axis_ecies::decrypt_with_domain(
+                    &ciphertext,
+                    bid_public_key,
+                    private_key,
+                    &salt,
+                    domain.as_bytes(),
+                )?,
+            };
+
+            check_expected_message(&message, assert_equals.as_deref())?;
+            let message = match unpad {
+                true => unpad_message(&message, pad_side)?,
+                false => message,
+            };
+
+            // Convert the message to a hex-encoded string (abi-encoded for single-block messages)
             let output = bytes_to_string(&message);
 
             // Print output to command line
-            println!("{}", output);
+            results.push(output);
+        }
+        Commands::DecryptBlob {
+            blob,
+            private_key,
+            salt,
+        } => {
+            // Parse the blob in the selected encoding, which must hold exactly one encrypted bid
+            // Will error if the bid public key is not on the curve
+            let mut positionals = Positionals::new([Some(blob)]);
+            let blob = positionals.next("blob", encoding::string_to_bytes)?;
+            positionals.finish()?;
+            let encrypted = decode_encrypted_bid(&blob)?;
+
+            // Convert private key to ark-bn254 type
+            // Will error if it is not below the bn254 group order
+            let private_key = scalar_from_biguint(private_key, "private key")?;
+
+            let message = axis_ecies::decrypt(
+                &encrypted.ciphertext,
+                encrypted.bid_public_key,
+                private_key,
+                &salt,
+            )?;
+            results.push(bytes_to_string(&message));
         }
         Commands::Salt {
             lot_id,
             bidder_address,
             amount,
         } => {
-            // Convert lot_id and amount to U256 to fix at 32 bytes initially (so we can slice later)
-            let lot_id = U256::from_big_endian(&lot_id.to_bytes_be());
-            let amount = U256::from_big_endian(&amount.to_bytes_be());
-
-            // Calculate the salt by taking the keccak256 hash of the lot_id, bidder_address, and amount
-            // We have to carefully pack this so the hash is accurate
-            let mut lot_id_bytes = [0u8; 32];
-            lot_id.to_big_endian(&mut lot_id_bytes);
-
-            let mut amount_bytes = [0u8; 32];
-            amount.to_big_endian(&mut amount_bytes);
-
-            let preimage = [
-                lot_id_bytes[20..].to_vec(),
-                bidder_address.as_bytes().to_vec(),
-                amount_bytes[20..].to_vec(),
-            ]
-            .concat();
-
-            let salt = ethers::utils::keccak256(preimage);
+            let salt = packed_salt(&lot_id, bidder_address, &amount);
 
             // Convert the salt to a hex-encoded string
             let output = bytes_to_string(&salt);
 
             // Print output to command line
-            println!("{}", output);
+            results.push(output);
+        }
+        Commands::SaltDerive {
+            lot_id,
+            bidder,
+            amount,
+        } => {
+            // abi.encode pads every field to a 32-byte word, with the address left-padded
+            let preimage = ethers::abi::encode(&[
+                ethers::abi::Token::Uint(lot_id),
+                ethers::abi::Token::Address(bidder),
+                ethers::abi::Token::Uint(amount),
+            ]);
+
+            results.push(bytes_to_string(&ethers::utils::keccak256(preimage)));
+        }
+        Commands::SealBid {
+            lot_id,
+            bidder,
+            amount,
+            public_key_x,
+            public_key_y,
+        } => {
+            // Construct public key from coordinates
+            // Will error if the point is not on the curve
+            let public_key = g1_from_coordinates(public_key_x, public_key_y, "public key")?;
+
+            // Derive the salt from the bid parameters, and encrypt the amount as a single 32-byte block
+            let salt = bid_salt(&lot_id, bidder, &amount)?;
+            let message =
+                to_bytes32(BigInt::<4>::try_from(amount.clone()).expect("amount fits in a uint96"));
+            let (bid_private_key, _) = axis_ecies::generate_keypair_with_rng(rng);
+            let encrypted = axis_ecies::encrypt(&message, public_key, bid_private_key, &salt)?;
+
+            // Print the encrypted bid, then the bid private key for the later reveal
+            // With --quiet, both are printed as one JSON object so stdout holds a single line
+            let encrypted_bid = format_encrypted_bid(encrypted, OutputFormat::Abi, false, None);
+            let bid_private_key = bytes_to_string(&to_bytes32(bid_private_key));
+            match quiet {
+                true => results.push(
+                    serde_json::json!({
+                        "encryptedBid": encrypted_bid,
+                        "bidPrivateKey": bid_private_key,
+                    })
+                    .to_string(),
+                ),
+                false => results.extend([encrypted_bid, bid_private_key]),
+            }
+        }
+        Commands::EncryptEmp {
+            lot_id,
+            bidder,
+            amount,
+            amount_out,
+            public_key_x,
+            public_key_y,
+            bid_private_key,
+            seed,
+        } => {
+            // Construct public key from coordinates, and convert bid private key to ark-bn254 type
+            // Will error if the point is not on the curve or the key is not below the bn254 group order
+            let public_key = g1_from_coordinates(public_key_x, public_key_y, "public key")?;
+            let bid_private_key = scalar_from_biguint(bid_private_key, "bid private key")?;
+
+            // Sample a random seed if none was given
+            // The random seed is printed to stderr, even with --quiet, so the bid can be reproduced
+            let seed = seed.unwrap_or_else(|| {
+                let seed = u128::from_be_bytes(
+                    axis_ecies::generate_salt_with_rng(rng)[..16]
+                        .try_into()
+                        .unwrap(),
+                );
+                eprintln!("seed: {}", seed);
+                seed
+            });
+
+            // Pack and encrypt the message with the salt the contract derives for the bid
+            let salt = bid_salt(&lot_id, bidder, &amount)?;
+            let message = pack_emp_message(amount_out, seed);
+            let encrypted = axis_ecies::encrypt(&message, public_key, bid_private_key, &salt)?;
+
+            results.push(format_encrypted_bid(
+                encrypted,
+                OutputFormat::Abi,
+                false,
+                None,
+            ));
+        }
+        Commands::DecryptEmp {
+            ciphertext,
+            bid_public_key_x,
+            bid_public_key_y,
+            private_key,
+            lot_id,
+            bidder,
+            amount,
+        } => {
+            // Construct bid public key from coordinates, and convert private key to ark-bn254 type
+            // Will error if the point is not on the curve or the key is not below the bn254 group order
+            let bid_public_key =
+                g1_from_coordinates(bid_public_key_x, bid_public_key_y, "bid public key")?;
+            let private_key = scalar_from_biguint(private_key, "private key")?;
+
+            // Decrypt the message with the salt the contract derives for the bid, and unpack it
+            let salt = bid_salt(&lot_id, bidder, &amount)?;
+            let message = axis_ecies::decrypt(&ciphertext, bid_public_key, private_key, &salt)?;
+            let (seed, masked_value, amount_out) =
+                unpack_emp_message(&message.try_into().expect("message is one 32-byte block"));
+
+            // Print the labeled fields to command line, one per line, or as one JSON object with --quiet
+            // The values are JSON strings, since they may not fit in a JSON number
+            match quiet {
+                true => results.push(
+                    serde_json::json!({
+                        "seed": seed.to_string(),
+                        "maskedValue": masked_value.to_string(),
+                        "amountOut": amount_out.to_string(),
+                    })
+                    .to_string(),
+                ),
+                false => {
+                    results.push(format!("seed: {}", seed));
+                    results.push(format!("masked value: {}", masked_value));
+                    results.push(format!("amount out: {}", amount_out));
+                }
+            }
+        }
+        Commands::Keygen { count: None } => {
+            // Sample a random keypair from a CSPRNG
+            let (private_key, public_key) = axis_ecies::generate_keypair_with_rng(rng);
+
+            // Print the private key and public key coordinates to command line, one per line
+            // With --quiet, they are printed as one JSON object, as --count prints each keypair
+            match quiet {
+                true => results.push(keypair_json(private_key, public_key).to_string()),
+                false => {
+                    results.push(bytes_to_string(&to_bytes32(private_key)));
+                    results.push(bytes_to_string(&to_bytes32(public_key.x)));
+                    results.push(bytes_to_string(&to_bytes32(public_key.y)));
+                }
+            }
+        }
+        Commands::Keygen { count: Some(count) } => {
+            // Sample each keypair independently, resampling the (vanishingly unlikely) repeated private key
+            let mut private_keys = std::collections::HashSet::with_capacity(count);
+            let mut keypairs = Vec::with_capacity(count);
+            while private_keys.len() < count {
+                let (private_key, public_key) = axis_ecies::generate_keypair_with_rng(rng);
+                if !private_keys.insert(private_key) {
+                    continue;
+                }
+                keypairs.push(keypair_json(private_key, public_key));
+            }
+
+            // Print each keypair as a JSON object on its own line, or all of them as one JSON array with --quiet
+            match quiet {
+                true => results.push(serde_json::Value::Array(keypairs).to_string()),
+                false => results.extend(keypairs.iter().map(|keypair| keypair.to_string())),
+            }
+        }
+        Commands::Roundtrip {
+            message,
+            public_key_x,
+            public_key_y,
+            bid_private_key,
+            private_key,
+            salt,
+        } => {
+            // Parse the message in the selected encoding
+            let mut positionals = Positionals::new([Some(message)]);
+            let message = positionals.next("message", parse_message)?;
+            positionals.finish()?;
+
+            // Convert private keys to ark-bn254 types
+            // Will error if either is not below the bn254 group order
+            let bid_private_key = scalar_from_biguint(bid_private_key, "bid private key")?;
+            let private_key = scalar_from_biguint(private_key, "private key")?;
+
+            // Construct public key from coordinates
+            // Will error if the point is not on the curve
+            let public_key = g1_from_coordinates(public_key_x, public_key_y, "public key")?;
+
+            // Encrypt the message using the auction public key and the bid private key
+            let encrypted = axis_ecies::encrypt(&message, public_key, bid_private_key, &salt)?;
+
+            // Decrypt the ciphertext using the bid public key and the auction private key
+            let recovered = axis_ecies::decrypt(
+                &encrypted.ciphertext,
+                encrypted.bid_public_key,
+                private_key,
+                &salt,
+            )?;
+
+            // Compare the recovered message with the original message
+            if !axis_ecies::constant_time_eq(&recovered, &message) {
+                return Err(Error::crypto(format!(
+                    "roundtrip mismatch\n  expected:  {}\n  recovered: {}",
+                    bytes_to_string(&message),
+                    bytes_to_string(&recovered)
+                )));
+            }
+
+            results.push("OK".to_string());
+        }
+        Commands::BatchEncrypt {
+            input,
+            public_key_x,
+            public_key_y,
+            format,
+            jobs,
+        } => {
+            // Load the records from the input file
+            let records: Vec<EncryptRecord> = read_json(&input)?;
+
+            // Construct public key from coordinates
+            // Will error if the point is not on the curve
+            let public_key = g1_from_coordinates(public_key_x, public_key_y, "public key")?;
+
+            // Encrypt the records across the thread pool, which defaults to one thread per CPU
+            // The results are collected in record order, so the output and the first error reported do not depend on scheduling
+            // Nothing is printed unless every record succeeds, since results are written at the end
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs.map_or(0, NonZeroUsize::get))
+                .build()
+                .map_err(|err| Error::io(format!("failed to start the thread pool: {}", err)))?;
+            let encrypted: Vec<Result<String>> = pool.install(|| {
+                records
+                    .par_iter()
+                    .enumerate()
+                    .map(|(index, record)| encrypt_record(index, record, public_key, format))
+                    .collect()
+            });
+            for result in encrypted {
+                results.push(result?);
+            }
+        }
+        Commands::Address {
+            public_key_x,
+            public_key_y,
+        } => {
+            // Construct public key from coordinates
+            // Will error if the point is not on the curve
+            let public_key = g1_from_coordinates(public_key_x, public_key_y, "public key")?;
+
+            // Derive the address and print it checksummed to command line
+            let address = Address::from(axis_ecies::derive_address(public_key));
+            results.push(ethers::utils::to_checksum(&address, None));
+        }
+        Commands::DerivePubkey { private_key } => {
+            // Calculate the public key using the private key
+            // Will error if the private key is zero, since it maps to the point at infinity
+            let public_key =
+                axis_ecies::derive_public_key(scalar_from_biguint(private_key, "private key")?)?;
+
+            // Print the public key coordinates to command line, one per line
+            results.push(bytes_to_string(&to_bytes32(public_key.x)));
+            results.push(bytes_to_string(&to_bytes32(public_key.y)));
+        }
+        Commands::Decompress { compressed } => {
+            // Parse the point in the selected encoding, and solve the curve equation for the y coordinate with the prefix's parity
+            let mut positionals = Positionals::new([Some(compressed)]);
+            let compressed = positionals.next("compressed", parse_compressed_point)?;
+            positionals.finish()?;
+            let point = axis_ecies::decompress(&compressed)?;
+
+            // Print the point coordinates to command line, one per line
+            results.push(bytes_to_string(&to_bytes32(point.x)));
+            results.push(bytes_to_string(&to_bytes32(point.y)));
+        }
+        Commands::Decode { blob, json } => {
+            // Parse the blob in the selected encoding, which must hold exactly one encrypted bid
+            let mut positionals = Positionals::new([Some(blob)]);
+            let blob = positionals.next("blob", encoding::string_to_bytes)?;
+            positionals.finish()?;
+            let encrypted = decode_encrypted_bid(&blob)?;
+
+            match json {
+                true => results.push(encrypted_bid_json(&encrypted, false, None).to_string()),
+                false => {
+                    results.push(format!(
+                        "ciphertext: {}",
+                        bytes_to_string(&encrypted.ciphertext)
+                    ));
+                    results.push(format!(
+                        "bid public key x: {}",
+                        bytes_to_string(&blob[32..64])
+                    ));
+                    results.push(format!(
+                        "bid public key y: {}",
+                        bytes_to_string(&blob[64..])
+                    ));
+                }
+            }
+        }
+        Commands::Convert { value, to } => {
+            // Print the value in the requested radix, independently of --encoding
+            let output = match to {
+                Radix::Dec => value.to_string(),
+                Radix::Hex => {
+                    let mut bytes = [0u8; 32];
+                    value.to_big_endian(&mut bytes);
+                    format!("0x{}", hex::encode(bytes))
+                }
+            };
+            results.push(output);
+        }
+        Commands::VerifyBid {
+            blob,
+            bid_private_key,
+        } => {
+            // Parse the blob in the selected encoding, which must hold exactly one encrypted bid
+            let mut positionals = Positionals::new([Some(blob)]);
+            let blob = positionals.next("blob", encoding::string_to_bytes)?;
+            positionals.finish()?;
+            let encrypted = decode_encrypted_bid(&blob)?;
+
+            // Derive the bid public key from the revealed private key and compare it to the embedded one
+            let expected = axis_ecies::derive_public_key(scalar_from_biguint(
+                bid_private_key,
+                "bid private key",
+            )?)?;
+            if encrypted.bid_public_key != expected {
+                return Err(Error::crypto(format!(
+                    "bid public key ({}, {}) does not match bid private key, which derives ({}, {})",
+                    bytes_to_string(&to_bytes32(encrypted.bid_public_key.x)),
+                    bytes_to_string(&to_bytes32(encrypted.bid_public_key.y)),
+                    bytes_to_string(&to_bytes32(expected.x)),
+                    bytes_to_string(&to_bytes32(expected.y)),
+                )));
+            }
+
+            results.push("bid public key matches".to_string());
+        }
+        Commands::Bench { iterations } => {
+            // Encrypt a single-block message to 2G with a fixed bid private key and salt
+            let public_key = axis_ecies::derive_public_key(ScalarField::from(2u64)).unwrap();
+            let bid_private_key = ScalarField::from(3u64);
+            let message = [1u8; 32];
+            let salt = [1u8; 32];
+
+            // Pass each output through black_box so the optimizer cannot elide the work
+            let start = std::time::Instant::now();
+            for _ in 0..iterations {
+                let encrypted = axis_ecies::encrypt(
+                    std::hint::black_box(&message),
+                    public_key,
+                    bid_private_key,
+                    &salt,
+                )?;
+                std::hint::black_box(encrypted);
+            }
+            let elapsed = start.elapsed();
+
+            // Print the timings to stderr
+            let seconds = elapsed.as_secs_f64();
+            eprintln!("iterations: {}", iterations);
+            eprintln!("total: {:.3} s", seconds);
+            if iterations > 0 {
+                eprintln!(
+                    "per op: {:.3} us",
+                    seconds * 1_000_000.0 / iterations as f64
+                );
+                eprintln!("ops/sec: {:.1}", iterations as f64 / seconds);
+            }
+        }
+        Commands::Difftest { iterations, seed } => {
+            // Seed the inputs so any mismatch the contract reports can be reproduced
+            // The seed is printed to stderr, even with --quiet, since it is needed to replay the run
+            // ChaCha20 is used rather than StdRng, whose output may change between rand versions
+            let seed = seed.unwrap_or_else(|| rng.next_u64());
+            eprintln!("seed: {}", seed);
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+
+            for _ in 0..iterations {
+                // Sample a recipient keypair, a bid private key, a single-block message and a salt
+                // Zero private keys are skipped, since the contract rejects them
+                let private_key = loop {
+                    let private_key = ScalarField::rand(&mut rng);
+                    if !private_key.is_zero() {
+                        break private_key;
+                    }
+                };
+                let bid_private_key = loop {
+                    let bid_private_key = ScalarField::rand(&mut rng);
+                    if !bid_private_key.is_zero() {
+                        break bid_private_key;
+                    }
+                };
+                let public_key = axis_ecies::derive_public_key(private_key)?;
+                let mut message = [0u8; 32];
+                let mut salt = [0u8; 32];
+                rng.fill_bytes(&mut message);
+                rng.fill_bytes(&mut salt);
+
+                let encrypted = axis_ecies::encrypt(&message, public_key, bid_private_key, &salt)?;
+
+                // Print hex regardless of --encoding, since the contract script parses the values as uint256
+                let fields = [
+                    message,
+                    to_bytes32(public_key.x),
+                    to_bytes32(public_key.y),
+                    to_bytes32(bid_private_key),
+                    salt,
+                    encrypted.ciphertext.try_into().unwrap(),
+                    to_bytes32(encrypted.bid_public_key.x),
+                    to_bytes32(encrypted.bid_public_key.y),
+                ];
+                results.push(
+                    fields
+                        .iter()
+                        .map(|field| format!("0x{}", hex::encode(field)))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+            }
+        }
+        Commands::VerifyVector { file } => {
+            let cases: Vec<VectorCase> = read_json(&file)?;
+
+            // Run every case, so the report shows all failures rather than only the first
+            let mut report = Vec::new();
+            let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+            for (index, case) in cases.iter().enumerate() {
+                let (name, outcome) = match case {
+                    VectorCase::Ecies(vector) => {
+                        (vector.name.as_deref(), verify_ecies_vector(vector))
+                    }
+                    VectorCase::Rsa => {
+                        skipped += 1;
+                        report.push(format!("case {}: skip: rsa cases are not supported", index));
+                        continue;
+                    }
+                };
+                let label = match name {
+                    Some(name) => format!("case {} ({})", index, name),
+                    None => format!("case {}", index),
+                };
+                match outcome {
+                    Ok(()) => {
+                        passed += 1;
+                        report.push(format!("{}: pass", label));
+                    }
+                    Err(reason) => {
+                        failed += 1;
+                        report.push(format!("{}: fail: {}", label, reason));
+                    }
+                }
+            }
+            report.push(format!(
+                "{} passed, {} failed, {} skipped",
+                passed, failed, skipped
+            ));
+
+            if failed > 0 {
+                return Err(Error::crypto(format!(
+                    "test vectors failed\n  {}",
+                    report.join("\n  ")
+                )));
+            }
+
+            // With --quiet, the report is printed as one JSON object with the counts and a line per case
+            match quiet {
+                true => {
+                    // The counts replace the summary line
+                    report.pop();
+                    results.push(
+                        serde_json::json!({
+                            "passed": passed,
+                            "failed": failed,
+                            "skipped": skipped,
+                            "cases": report,
+                        })
+                        .to_string(),
+                    );
+                }
+                false => results.extend(report),
+            }
+        }
+        Commands::ValidatePubkey { x, y } => {
+            // Run the checks in order, so a failure can report which ones passed before it
+            // The EVM encodes the identity as (0, 0), which would otherwise fail as an off-curve point
+            // A coordinate at or above the field modulus does not encode a point, so it fails the on-curve check
+            let modulus = BigUint::from(BaseField::MODULUS);
+            let failed = if x.bits() == 0 && y.bits() == 0 {
+                Some(PublicKeyCheck::NotIdentity)
+            } else if x >= modulus || y >= modulus {
+                Some(PublicKeyCheck::OnCurve)
+            } else {
+                match axis_ecies::point_from_coordinates(BaseField::from(x), BaseField::from(y)) {
+                    Ok(_) => None,
+                    Err(axis_ecies::Error::NotInSubgroup) => Some(PublicKeyCheck::InSubgroup),
+                    Err(_) => Some(PublicKeyCheck::OnCurve),
+                }
+            };
+
+            let mut passed = true;
+            let report = PublicKeyCheck::ALL.iter().map(|check| {
+                let status = match (passed, Some(*check) == failed) {
+                    (false, _) => "skipped",
+                    (true, true) => {
+                        passed = false;
+                        "fail"
+                    }
+                    (true, false) => "pass",
+                };
+                format!("{}: {}", check.name(), status)
+            });
+
+            match failed {
+                Some(check) => {
+                    return Err(Error::PublicKey {
+                        check,
+                        report: report.collect::<Vec<_>>().join("\n  "),
+                    })
+                }
+                None => results.extend(report),
+            }
+        }
+        Commands::Version => {
+            results.push(format!("ecies-cli {}", env!("CARGO_PKG_VERSION")));
+            results.push(format!("commit: {}", env!("ECIES_CLI_GIT_COMMIT")));
+            results.push("curve: bn254 (alt_bn128)".to_string());
+            results.push("hash: keccak256".to_string());
+            results.push("cipher: xor (default), aes-gcm".to_string());
+            results.push(
+                "padding: messages of up to 32 bytes are left-padded to one 32-byte block"
+                    .to_string(),
+            );
+        }
+        Commands::Repl => return Err(Error::parse("the repl is already running")),
+        Commands::Completions { shell } => {
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "ecies-cli", &mut script);
+            results.push(String::from_utf8_lossy(&script).trim_end().to_string());
         }
     }
 
+    Ok(results)
+}
+
+// Helper function to stream an input file through an encryptor or decryptor into an output file
+// Parent directories of the output file are created as needed, and the output is removed if streaming fails,
+// so a partial or unauthenticated message is never left behind
+fn stream_file<T>(
+    input: &Path,
+    output: &Path,
+    quiet: bool,
+    stream: impl FnOnce(&mut BufReader<File>, &mut BufWriter<File>) -> Result<T, axis_ecies::Error>,
+) -> Result<T> {
+    let mut reader = File::open(input)
+        .map(BufReader::new)
+        .map_err(|err| Error::io(format!("failed to read {}: {}", input.display(), err)))?;
+    let write_error =
+        |err: std::io::Error| Error::io(format!("failed to write {}: {}", output.display(), err));
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    let mut writer = File::create(output)
+        .map(BufWriter::new)
+        .map_err(write_error)?;
+
+    let streamed = stream(&mut reader, &mut writer).map_err(|err| {
+        let _ = std::fs::remove_file(output);
+        match err {
+            axis_ecies::Error::Io(kind) => Error::io(format!(
+                "failed to stream {} to {}: {}",
+                input.display(),
+                output.display(),
+                kind
+            )),
+            err => Error::from(err),
+        }
+    })?;
+
+    if !quiet {
+        eprintln!("wrote {}", output.display());
+    }
+    Ok(streamed)
+}
+
+// Helper function to write the result lines to the output file if given, otherwise to stdout
+// Parent directories of the output file are created as needed, and the written path is reported unless quiet
+// With --quiet, any other multi-line result is written as one JSON array of its lines, so the output is always one line
+fn write_results(results: &[String], output: Option<&Path>, quiet: bool) -> Result<()> {
+    let joined;
+    let results = match (quiet, results.len()) {
+        (true, 2..) => {
+            joined = [serde_json::json!(results).to_string()];
+            &joined[..]
+        }
+        _ => results,
+    };
+
+    let Some(path) = output else {
+        for result in results {
+            println!("{}", result);
+        }
+        return Ok(());
+    };
+
+    let io_error =
+        |err: std::io::Error| Error::io(format!("failed to write {}: {}", path.display(), err));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let contents: String = results
+        .iter()
+        .map(|result| format!("{}\n", result))
+        .collect();
+    std::fs::write(path, contents).map_err(io_error)?;
+
+    if !quiet {
+        eprintln!("wrote {}", path.display());
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    #[test]
+    fn g1_from_coordinates_rejects_off_curve_point() {
+        // (1, 1) does not satisfy y^2 = x^3 + 3
+        let err =
+            g1_from_coordinates(BigUint::from(1u8), BigUint::from(1u8), "public key").unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("public key (x, y) is not a valid bn254 G1 point"));
+    }
+
+    #[test]
+    fn g1_from_coordinates_accepts_generator() {
+        let point =
+            g1_from_coordinates(BigUint::from(1u8), BigUint::from(2u8), "public key").unwrap();
+
+        assert_eq!(point, G1::generator());
+    }
+
+    #[test]
+    fn g1_from_coordinates_subgroup_check_passes_for_on_curve_points() {
+        // bn254 G1 has cofactor 1, so no on-curve point lies outside the prime-order subgroup
+        // A point derived from an arbitrary scalar must pass both checks
+        let expected = (G1::generator() * ScalarField::from(123_456_789u64)).into_affine();
+        let point = g1_from_coordinates(
+            BigUint::from(expected.x),
+            BigUint::from(expected.y),
+            "public key",
+        )
+        .unwrap();
+
+        assert_eq!(point, expected);
+    }
+
+    #[test]
+    fn single_block_message_rejects_long_message() {
+        let message = Bytes::from((0u8..40).collect::<Vec<u8>>());
+
+        let err = single_block_message(message.clone(), false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("message is 40 bytes, which exceeds the 32-byte single block"));
+
+        let truncated = single_block_message(message, true).unwrap();
+        assert_eq!(truncated.to_vec(), (8u8..40).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn emp_message_roundtrips_amount_out_through_seed_mask() {
+        // The seed is larger than the amount out, so the masked value wraps around
+        let seed = u128::MAX - 5;
+        let message = pack_emp_message(100, seed);
+        assert_eq!(message[..16], seed.to_be_bytes());
+        assert_eq!(unpack_emp_message(&message), (seed, 106, 100));
+    }
+
+    #[test]
+    fn g1_from_coordinates_rejects_unreduced_coordinate() {
+        // p + 1 reduces to the generator's x coordinate 1
+        let modulus = BigUint::from(BaseField::MODULUS);
+        let err = g1_from_coordinates(modulus + 1u8, BigUint::from(2u8), "public key").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("public key x: coordinate exceeds bn254 base field modulus"));
+    }
+
+    #[test]
+    fn scalar_from_biguint_rejects_unreduced_scalar() {
+        let modulus = BigUint::from(ScalarField::MODULUS);
+        assert!(scalar_from_biguint(&modulus - 1u8, "private key").is_ok());
+
+        let err = scalar_from_biguint(modulus, "private key").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("private key: scalar exceeds bn254 group order"));
+    }
+
+    #[test]
+    fn pad_message_roundtrips_on_both_sides() {
+        let message = [0x12, 0x34, 0x80, 0x00];
+        for side in [PadSide::Right, PadSide::Left] {
+            let padded = pad_message(&message, 16, side).unwrap();
+            assert_eq!(padded.len(), 16);
+            assert_eq!(unpad_message(&padded, side).unwrap(), message);
+        }
+
+        assert_eq!(
+            pad_message(&[0xab], 4, PadSide::Right).unwrap(),
+            [0xab, 0x80, 0, 0]
+        );
+        assert_eq!(
+            pad_message(&[0xab], 4, PadSide::Left).unwrap(),
+            [0, 0, 0x80, 0xab]
+        );
+        assert!(pad_message(&[0xab; 4], 4, PadSide::Right).is_err());
+        assert!(unpad_message(&[0xab, 0, 0, 0], PadSide::Right).is_err());
+    }
+
+    #[test]
+    fn recipients_match_single_recipient_encrypt() {
+        let recipients = [
+            ("0x1", "0x2"),
+            (
+                "0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+                "0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+            ),
+        ];
+        let records: Vec<_> = recipients
+            .iter()
+            .map(|(x, y)| serde_json::json!({ "x": x, "y": y }))
+            .collect();
+        let path = std::env::temp_dir().join(format!(
+            "ecies-cli-{}-recipients-match-single-recipient-encrypt.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, serde_json::Value::Array(records).to_string()).unwrap();
+
+        let seed = 7;
+        let args = Cli::parse_from([
+            "ecies-cli",
+            "encrypt",
+            "--recipients",
+            path.to_str().unwrap(),
+            "5",
+            "1",
+        ]);
+        let output = run(args, &mut rand_chacha::ChaCha20Rng::seed_from_u64(seed)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&output[0]).unwrap();
+
+        // The bid private key is the first keypair sampled from the same seed
+        let (bid_private_key, _) = axis_ecies::generate_keypair_with_rng(
+            &mut rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+        );
+        let bid_private_key = bytes_to_string(&to_bytes32(bid_private_key));
+
+        assert_eq!(entries.len(), recipients.len());
+        for (index, ((x, y), entry)) in recipients.iter().zip(entries).enumerate() {
+            let args = Cli::parse_from([
+                "ecies-cli",
+                "encrypt",
+                "--format",
+                "json",
+                "5",
+                x,
+                y,
+                &bid_private_key,
+                "1",
+            ]);
+            let single = run(args, &mut rand_chacha::ChaCha20Rng::seed_from_u64(seed)).unwrap();
+            let mut single: serde_json::Value = serde_json::from_str(&single[0]).unwrap();
+            single["recipient"] = serde_json::json!(index);
+            assert_eq!(entry, single);
+        }
+    }
+
+    #[test]
+    fn seeded_rng_makes_keygen_reproducible() {
+        let keygen = |seed| {
+            let args = Cli::parse_from(["ecies-cli", "keygen", "--count", "2"]);
+            run(args, &mut rand_chacha::ChaCha20Rng::seed_from_u64(seed)).unwrap()
+        };
+
+        assert_eq!(keygen(1), keygen(1));
+        assert_ne!(keygen(1), keygen(2));
+    }
+}
@@ -0,0 +1,117 @@
+// Interactive mode, which reads command lines from stdin and runs each one as if it were its own invocation
+
+use crate::error::{Error, Result};
+use crate::{run, write_results, Cli};
+use clap::Parser;
+use rand::rngs::OsRng;
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// Set while the repl owns stdin, so commands cannot read their inputs from it
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+// Helper function to check whether the repl is reading stdin
+pub fn running() -> bool {
+    RUNNING.load(Ordering::Relaxed)
+}
+
+// Helper function to run the repl until stdin is closed
+// Errors in one line are reported in the session's error format, and the loop carries on with the next line
+// The session's --quiet, --verbose and --strict apply to every line, while --output must be given per line, since each
+// command would otherwise overwrite the previous command's result
+pub fn repl(session: &Cli) -> Result<()> {
+    if session.output.is_some() {
+        return Err(Error::parse(
+            "--output cannot be set when starting the repl, pass it to each command instead",
+        ));
+    }
+    RUNNING.store(true, Ordering::Relaxed);
+
+    let interactive = std::io::stdin().is_terminal();
+    let mut variables = BTreeMap::new();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if interactive {
+            eprint!("ecies> ");
+            let _ = std::io::stderr().flush();
+        }
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line.map_err(|err| Error::io(format!("failed to read stdin: {}", err)))?;
+
+        if let Err(err) = run_line(session, &mut variables, &line) {
+            err.report(session.error_format);
+        }
+    }
+}
+
+// Helper function to run one line of input
+// Blank lines and lines starting with # are ignored
+fn run_line(session: &Cli, variables: &mut BTreeMap<String, String>, line: &str) -> Result<()> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words[..] {
+        [] => Ok(()),
+        [first, ..] if first.starts_with('#') => Ok(()),
+        ["set", ref assignments @ ..] => set(variables, assignments),
+        _ => {
+            // Substitute $name with the value of the variable before parsing, as a shell would
+            let words = words
+                .iter()
+                .map(|word| match word.strip_prefix('$') {
+                    Some(name) => variables
+                        .get(name)
+                        .cloned()
+                        .ok_or_else(|| Error::parse(format!("variable '{}' is not set", name))),
+                    None => Ok(word.to_string()),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // The encoding is fixed for the process, so it can only be chosen when starting the repl
+            if words.iter().any(|word| word.starts_with("--encoding")) {
+                return Err(Error::parse(
+                    "--encoding can only be set when starting the repl",
+                ));
+            }
+            let mut args =
+                Cli::try_parse_from(std::iter::once("ecies-cli".to_string()).chain(words))?;
+
+            // Inherit the session's --quiet or --verbose, unless the line asks for the other one
+            args.quiet |= session.quiet && !args.verbose;
+            args.verbose |= session.verbose && !args.quiet;
+            args.strict |= session.strict;
+
+            let (output, quiet) = (args.output.clone(), args.quiet);
+            let results = run(args, &mut OsRng)?;
+            write_results(&results, output.as_deref(), quiet)
+        }
+    }
+}
+
+// Helper function to handle "set name=value", or list the variables for a bare "set"
+fn set(variables: &mut BTreeMap<String, String>, assignments: &[&str]) -> Result<()> {
+    if assignments.is_empty() {
+        for (name, value) in variables.iter() {
+            println!("{}={}", name, value);
+        }
+        return Ok(());
+    }
+
+    for assignment in assignments {
+        match assignment.split_once('=') {
+            Some((name, value)) if !name.is_empty() => {
+                variables.insert(name.to_string(), value.to_string());
+            }
+            _ => {
+                return Err(Error::parse(format!(
+                    "'{}' is not of the form name=value",
+                    assignment
+                )))
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// Helpers for reading an auction's public key from the chain over JSON-RPC
+
+use crate::error::{Error, Result};
+use ethers::{
+    abi::{HumanReadableParser, Token},
+    providers::{Http, Middleware, Provider},
+    types::{transaction::eip2718::TypedTransaction, Address, TransactionRequest, U256},
+};
+use num_bigint::BigUint;
+
+// The AuctionHouse getter for the auction module that a lot was created with
+const GET_AUCTION_MODULE_FOR_ID: &str =
+    "function getAuctionModuleForId(uint96 lotId) external view returns (address)";
+
+// The EncryptedMarginalPrice getter for a lot's AuctionData, which omits the dynamic bidIds array
+// The public key is the ninth field
+const AUCTION_DATA: &str = "function auctionData(uint96 lotId) external view returns (uint64 nextBidId, uint64 nextDecryptIndex, uint8 status, uint64 marginalBidId, uint256 marginalPrice, uint256 minPrice, uint256 minFilled, uint256 minBidSize, (uint256, uint256) publicKey, uint256 privateKey)";
+
+// Helper function to fetch the auction public key for a lot, returning its coordinates
+// The lot's module is looked up through the auction house, and must be an EncryptedMarginalPrice module
+pub fn fetch_auction_public_key(
+    rpc: &str,
+    auction_house: Address,
+    lot_id: u128,
+) -> Result<(BigUint, BigUint)> {
+    if lot_id >> 96 != 0 {
+        return Err(Error::validation(format!(
+            "lot id {} does not fit in a uint96",
+            lot_id
+        )));
+    }
+    let provider = Provider::<Http>::try_from(rpc)
+        .map_err(|err| Error::parse(format!("invalid RPC url '{}': {}", rpc, err)))?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| Error::io(format!("failed to start the RPC client: {}", err)))?;
+    let lot_id = Token::Uint(U256::from(lot_id));
+
+    let module = match &call(
+        &runtime,
+        &provider,
+        auction_house,
+        GET_AUCTION_MODULE_FOR_ID,
+        lot_id.clone(),
+    )?[..]
+    {
+        [Token::Address(module)] => *module,
+        _ => unreachable!("getAuctionModuleForId returns one address"),
+    };
+    let auction_data = call(&runtime, &provider, module, AUCTION_DATA, lot_id)?;
+    match &auction_data[8] {
+        Token::Tuple(point) => match &point[..] {
+            [Token::Uint(x), Token::Uint(y)] => Ok((u256_to_biguint(*x), u256_to_biguint(*y))),
+            _ => unreachable!("publicKey is a tuple of two uint256"),
+        },
+        _ => unreachable!("publicKey is a tuple"),
+    }
+}
+
+// Helper function to make one eth_call to a view function and decode its outputs
+fn call(
+    runtime: &tokio::runtime::Runtime,
+    provider: &Provider<Http>,
+    to: Address,
+    signature: &str,
+    lot_id: Token,
+) -> Result<Vec<Token>> {
+    let function = HumanReadableParser::parse_function(signature)
+        .expect("function signatures are valid human-readable ABI");
+    let data = function
+        .encode_input(&[lot_id])
+        .expect("the lot id matches the uint96 input");
+    let transaction: TypedTransaction = TransactionRequest::new().to(to).data(data).into();
+
+    let output = runtime
+        .block_on(provider.call(&transaction, None))
+        .map_err(|err| {
+            Error::io(format!(
+                "{} call to {:?} failed: {}",
+                function.name, to, err
+            ))
+        })?;
+    function.decode_output(&output).map_err(|err| {
+        Error::validation(format!(
+            "{:?} returned an unexpected {} result, so it may not be an EncryptedMarginalPrice lot: {}",
+            to, function.name, err
+        ))
+    })
+}
+
+fn u256_to_biguint(value: U256) -> BigUint {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    BigUint::from_bytes_be(&bytes)
+}
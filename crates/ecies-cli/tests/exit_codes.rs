@@ -0,0 +1,208 @@
+// Tests for the exit code contract: 2 for argument and I/O errors, 3 for validation failures, 4 for crypto failures, 5-7 for validate-pubkey
+
+use std::process::Command;
+
+// Helper function to run the CLI and return its exit code
+fn exit_code(args: &[&str]) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+        .code()
+        .unwrap()
+}
+
+#[test]
+fn success_exits_with_0() {
+    assert_eq!(exit_code(&["derive-pubkey", "2"]), 0);
+}
+
+#[test]
+fn argument_errors_exit_with_2() {
+    assert_eq!(exit_code(&["derive-pubkey", "not-a-number"]), 2);
+    assert_eq!(exit_code(&["decrypt", "1", "1", "2"]), 2);
+}
+
+#[test]
+fn missing_input_files_exit_with_2_and_the_io_kind() {
+    let missing =
+        std::env::temp_dir().join(format!("ecies-cli-{}-missing.json", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args([
+            "--error-format",
+            "json",
+            "verify-vector",
+            missing.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(error["kind"], "io");
+    assert_eq!(error["code"], 2);
+}
+
+#[test]
+fn validation_failures_exit_with_3() {
+    // (1, 1) is not on the curve
+    assert_eq!(exit_code(&["encrypt", "1", "1", "1", "3", "1"]), 3);
+
+    // The bn254 group order itself is out of range
+    let group_order =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+    assert_eq!(exit_code(&["derive-pubkey", group_order]), 3);
+}
+
+#[test]
+fn safety_checks_are_enforced_by_default() {
+    // The bn254 base field modulus plus one, which would otherwise reduce to the generator's x coordinate
+    let unreduced_x =
+        "21888242871839275222246405745257275088696311157297823662689037894645226208584";
+    assert_eq!(exit_code(&["encrypt", "1", unreduced_x, "2", "3", "1"]), 3);
+
+    // A zero bid private key would make the bid public key and the shared secret the identity
+    assert_eq!(exit_code(&["encrypt", "1", "1", "2", "0", "1"]), 3);
+
+    // A zero private key would make the shared secret the identity
+    let ciphertext = format!("0x{}", "11".repeat(32));
+    assert_eq!(exit_code(&["decrypt", &ciphertext, "1", "2", "0", "1"]), 3);
+}
+
+#[test]
+fn strict_mode_enforces_every_safety_check() {
+    // 2G, a valid recipient public key that --strict accepts
+    let x = "0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3";
+    let y = "0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+    assert_eq!(exit_code(&["--strict", "encrypt", "1", x, y, "3", "1"]), 0);
+
+    // The checks that always run also fire under --strict
+    let unreduced_x =
+        "21888242871839275222246405745257275088696311157297823662689037894645226208584";
+    let group_order =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+    assert_eq!(
+        exit_code(&["--strict", "encrypt", "1", unreduced_x, "2", "3", "1"]),
+        3
+    );
+    assert_eq!(
+        exit_code(&["--strict", "encrypt", "1", "1", "1", "3", "1"]),
+        3
+    );
+    assert_eq!(exit_code(&["--strict", "encrypt", "1", x, y, "0", "1"]), 3);
+    assert_eq!(
+        exit_code(&["--strict", "encrypt", "1", x, y, group_order, "1"]),
+        3
+    );
+    let ciphertext = format!("0x{}", "11".repeat(32));
+    assert_eq!(
+        exit_code(&["--strict", "decrypt", &ciphertext, x, y, "0", "1"]),
+        3
+    );
+
+    // The generator as a public key, and 1 as a private key
+    assert_eq!(
+        exit_code(&["--strict", "encrypt", "1", "1", "2", "3", "1"]),
+        3
+    );
+    assert_eq!(exit_code(&["--strict", "encrypt", "1", x, y, "1", "1"]), 3);
+    assert_eq!(
+        exit_code(&["--strict", "decrypt", &ciphertext, x, y, "1", "1"]),
+        3
+    );
+
+    // An all-zero salt
+    assert_eq!(
+        exit_code(&["--strict", "encrypt", "--zero-salt", "1", x, y, "3"]),
+        3
+    );
+    assert_eq!(
+        exit_code(&["--strict", "decrypt", "--zero-salt", &ciphertext, x, y, "2"]),
+        3
+    );
+
+    // A single-block message that --truncate would cut
+    let message = format!("0x{}", "11".repeat(33));
+    let args = [
+        "encrypt",
+        "--single-block",
+        "--truncate",
+        "--message-bytes",
+        &message,
+        x,
+        y,
+        "3",
+        "1",
+    ];
+    assert_eq!(exit_code(&args), 0);
+    assert_eq!(exit_code(&[&["--strict"][..], &args[..]].concat()), 3);
+}
+
+#[test]
+fn crypto_failures_exit_with_4() {
+    // The tag is all zeroes, so it cannot match the ciphertext
+    let ciphertext = format!("0x{}{}", "11".repeat(32), "00".repeat(32));
+    assert_eq!(
+        exit_code(&[
+            "decrypt",
+            "--authenticated",
+            &ciphertext,
+            "1",
+            "2",
+            "2",
+            "1"
+        ]),
+        4
+    );
+
+    // The private key 3 does not correspond to the public key G
+    assert_eq!(exit_code(&["roundtrip", "1", "1", "2", "3", "3", "1"]), 4);
+
+    // Decrypting with bid public key G and private key 2 does not recover the expected zero message
+    assert_eq!(
+        exit_code(&["decrypt", "--assert-equals", "0", "1", "1", "2", "2", "1"]),
+        4
+    );
+}
+
+#[test]
+fn validate_pubkey_failures_exit_with_their_own_code() {
+    assert_eq!(exit_code(&["validate-pubkey", "1", "2"]), 0);
+    assert_eq!(exit_code(&["validate-pubkey", "1", "1"]), 5);
+    assert_eq!(exit_code(&["validate-pubkey", "0", "0"]), 7);
+}
+
+#[test]
+fn json_errors_report_the_kind_and_exit_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(["--error-format", "json", "encrypt", "1", "1", "1", "3", "1"])
+        .output()
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(error["kind"], "validation");
+    assert_eq!(error["code"], 3);
+}
+
+#[test]
+fn aes_gcm_rejects_a_chosen_salt() {
+    assert_eq!(
+        exit_code(&["encrypt", "--cipher", "aes-gcm", "1", "1", "2", "3", "1"]),
+        2
+    );
+    assert_eq!(
+        exit_code(&[
+            "encrypt",
+            "--cipher",
+            "aes-gcm",
+            "--random-salt",
+            "1",
+            "1",
+            "2",
+            "3"
+        ]),
+        0
+    );
+}
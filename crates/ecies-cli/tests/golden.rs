@@ -0,0 +1,390 @@
+// Golden vectors asserting the CLI reproduces the on-chain ECIES library byte-for-byte
+// The values are taken from the assertions in test/lib/ECIES, which the EncryptedMarginalPrice module relies on
+
+use ethers::{
+    abi::{encode_packed, Token},
+    types::Address,
+    utils::{hex, keccak256},
+};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+// The recipient public key 2G, as computed by ECIES.calcPubKey(Point(1, 2), 2)
+const RECIPIENT_PUBLIC_KEY_X: &str =
+    "0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3";
+const RECIPIENT_PUBLIC_KEY_Y: &str =
+    "0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+
+// The expected outputs from ECIES.encrypt(1, 2G, 3, 1)
+const CIPHERTEXT: &str = "0xf96d7675ae04b89c9b5a9b0613d3530bb939186d05959efba9b3249a461abbc4";
+const BID_PUBLIC_KEY_X: &str = "0x0769bf9ac56bea3ff40232bcb1b6bd159315d84715b8e679f2d355961915abf0";
+const BID_PUBLIC_KEY_Y: &str = "0x2ab799bee0489429554fdb7c8d086475319e63b40b9c5b57cdf1ff3dd9fe2261";
+
+// Helper function to get a path in the temp directory that is unique to this test process
+// so concurrent runs from different checkouts cannot collide
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("ecies-cli-{}-{}", std::process::id(), name))
+}
+
+// Helper function to run the CLI and return its trimmed stdout
+fn ecies_cli(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "ecies-cli {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn encrypt_matches_contract() {
+    let output = ecies_cli(&[
+        "encrypt",
+        "1",
+        RECIPIENT_PUBLIC_KEY_X,
+        RECIPIENT_PUBLIC_KEY_Y,
+        "3",
+        "1",
+    ]);
+
+    assert_eq!(
+        output,
+        format!(
+            "{}{}{}",
+            CIPHERTEXT,
+            &BID_PUBLIC_KEY_X[2..],
+            &BID_PUBLIC_KEY_Y[2..]
+        )
+    );
+}
+
+#[test]
+fn encrypt_pubkey_blob_matches_contract() {
+    let blob = format!("{}{}", RECIPIENT_PUBLIC_KEY_X, &RECIPIENT_PUBLIC_KEY_Y[2..]);
+    let output = ecies_cli(&["encrypt", "--pubkey-blob", &blob, "1", "3", "1"]);
+
+    assert_eq!(
+        output,
+        format!(
+            "{}{}{}",
+            CIPHERTEXT,
+            &BID_PUBLIC_KEY_X[2..],
+            &BID_PUBLIC_KEY_Y[2..]
+        )
+    );
+}
+
+#[test]
+fn decrypt_matches_contract() {
+    let output = ecies_cli(&[
+        "decrypt",
+        CIPHERTEXT,
+        BID_PUBLIC_KEY_X,
+        BID_PUBLIC_KEY_Y,
+        "2",
+        "1",
+    ]);
+
+    assert_eq!(output, format!("0x{:064x}", 1));
+}
+
+#[test]
+fn decrypt_blob_matches_contract() {
+    let blob = format!(
+        "{}{}{}",
+        CIPHERTEXT,
+        &BID_PUBLIC_KEY_X[2..],
+        &BID_PUBLIC_KEY_Y[2..]
+    );
+    let output = ecies_cli(&["decrypt-blob", &blob, "2", "1"]);
+
+    assert_eq!(output, format!("0x{:064x}", 1));
+}
+
+#[test]
+fn decrypt_params_file_matches_contract() {
+    // The positional private key overrides the one in the file
+    let params = temp_path("decrypt-params.json");
+    std::fs::write(
+        &params,
+        format!(
+            r#"{{"ciphertext": "{}", "bidPublicKeyX": "{}", "bidPublicKeyY": "{}", "privateKey": "5", "salt": 1}}"#,
+            CIPHERTEXT, BID_PUBLIC_KEY_X, BID_PUBLIC_KEY_Y
+        ),
+    )
+    .unwrap();
+
+    let output = ecies_cli(&[
+        "decrypt",
+        "--params",
+        params.to_str().unwrap(),
+        CIPHERTEXT,
+        BID_PUBLIC_KEY_X,
+        BID_PUBLIC_KEY_Y,
+        "2",
+    ]);
+    std::fs::remove_file(&params).unwrap();
+
+    assert_eq!(output, format!("0x{:064x}", 1));
+}
+
+#[test]
+fn salt_matches_contract() {
+    // The contract salt is keccak256(abi.encodePacked(uint96 lotId, address bidder, uint96 amount))
+    let bidder = "0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496";
+    let amount = "1000000000000000000";
+    // uint96 values are packed as their low 12 bytes
+    let packed = encode_packed(&[
+        Token::FixedBytes(1u128.to_be_bytes()[4..].to_vec()),
+        Token::Address(bidder.parse::<Address>().unwrap()),
+        Token::FixedBytes(1_000_000_000_000_000_000u128.to_be_bytes()[4..].to_vec()),
+    ])
+    .unwrap();
+
+    let output = ecies_cli(&["salt", "1", bidder, amount]);
+    assert_eq!(output, format!("0x{}", hex::encode(keccak256(packed))));
+}
+
+#[test]
+fn salt_derive_matches_abi_encode() {
+    // keccak256(abi.encode(uint96(1), address(this), uint96(1e18))) as computed in test/lib/ECIES/salt.t.sol
+    let output = ecies_cli(&[
+        "salt-derive",
+        "1",
+        "0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496",
+        "1000000000000000000",
+    ]);
+    assert_eq!(
+        output,
+        "0xc0ddb7e6af41b34e4d2013fc015b12dd5e77b84776a4d4fac718dcd64bfd8ea8"
+    );
+}
+
+#[test]
+fn repl_matches_contract() {
+    let mut repl = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    write!(
+        repl.stdin.take().unwrap(),
+        "set x={} y={}\nencrypt 1 $x $y 3 1\ndecrypt {} {} {} 2 1\n",
+        RECIPIENT_PUBLIC_KEY_X,
+        RECIPIENT_PUBLIC_KEY_Y,
+        CIPHERTEXT,
+        BID_PUBLIC_KEY_X,
+        BID_PUBLIC_KEY_Y
+    )
+    .unwrap();
+    let output = repl.wait_with_output().unwrap();
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{}{}{}\n0x{:064x}\n",
+            CIPHERTEXT,
+            &BID_PUBLIC_KEY_X[2..],
+            &BID_PUBLIC_KEY_Y[2..],
+            1
+        )
+    );
+}
+
+#[test]
+fn repl_applies_session_quiet_and_rejects_session_output() {
+    let mut repl = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(["--quiet", "repl"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(repl.stdin.take().unwrap(), "keygen").unwrap();
+    let output = repl.wait_with_output().unwrap();
+
+    // keygen prints its keypair as one JSON line under --quiet
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1);
+    serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args([
+            "--output",
+            temp_path("repl-output.txt").to_str().unwrap(),
+            "repl",
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn batch_encrypt_parallel_output_matches_serial() {
+    let records: Vec<String> = (1..=64)
+        .map(|i| {
+            format!(
+                r#"{{"message": "{}", "bidPrivateKey": "{}", "salt": "{}"}}"#,
+                i,
+                i + 2,
+                i
+            )
+        })
+        .collect();
+    let path = temp_path("batch-encrypt-parallel.json");
+    std::fs::write(&path, format!("[{}]", records.join(","))).unwrap();
+    let input = path.to_str().unwrap();
+
+    let serial = ecies_cli(&["batch-encrypt", "--jobs", "1", input, "1", "2"]);
+    let parallel = ecies_cli(&["batch-encrypt", "--jobs", "8", input, "1", "2"]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(serial.lines().count(), 64);
+    assert_eq!(parallel, serial);
+}
+
+#[test]
+fn verify_vector_accepts_contract_vector_and_rejects_tampered_one() {
+    let vector = |ciphertext: &str| {
+        format!(
+            r#"{{"type": "ecies", "message": "1", "publicKeyX": "{}", "publicKeyY": "{}", "bidPrivateKey": "3", "salt": "1", "ciphertext": "{}", "bidPublicKeyX": "{}", "bidPublicKeyY": "{}", "privateKey": "2"}}"#,
+            RECIPIENT_PUBLIC_KEY_X,
+            RECIPIENT_PUBLIC_KEY_Y,
+            ciphertext,
+            BID_PUBLIC_KEY_X,
+            BID_PUBLIC_KEY_Y
+        )
+    };
+    let vectors = temp_path("verify-vector.json");
+
+    std::fs::write(&vectors, format!("[{}]", vector(CIPHERTEXT))).unwrap();
+    let output = ecies_cli(&["verify-vector", vectors.to_str().unwrap()]);
+    assert_eq!(output, "case 0: pass\n1 passed, 0 failed, 0 skipped");
+
+    // Flip the last bit of the ciphertext
+    let tampered = format!("{}5", &CIPHERTEXT[..CIPHERTEXT.len() - 1]);
+    std::fs::write(&vectors, format!("[{}]", vector(&tampered))).unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(["verify-vector", vectors.to_str().unwrap()])
+        .output()
+        .unwrap()
+        .status;
+    std::fs::remove_file(&vectors).unwrap();
+    assert_eq!(status.code(), Some(4));
+}
+
+#[test]
+fn pad_to_roundtrips_through_unpad_on_both_sides() {
+    for side in ["right", "left"] {
+        let encrypted = ecies_cli(&[
+            "encrypt",
+            "--format",
+            "json",
+            "--pad-to",
+            "40",
+            "--pad-side",
+            side,
+            "0xabcdef",
+            RECIPIENT_PUBLIC_KEY_X,
+            RECIPIENT_PUBLIC_KEY_Y,
+            "3",
+            "1",
+        ]);
+        let encrypted: serde_json::Value = serde_json::from_str(&encrypted).unwrap();
+        let field = |name: &str| encrypted[name].as_str().unwrap().to_string();
+        assert_eq!(field("ciphertext").len(), 2 + 2 * 40);
+
+        let decrypted = ecies_cli(&[
+            "decrypt",
+            "--unpad",
+            "--pad-side",
+            side,
+            &field("ciphertext"),
+            &field("bidPublicKeyX"),
+            &field("bidPublicKeyY"),
+            "2",
+            "1",
+        ]);
+        assert_eq!(decrypted, "0xabcdef");
+    }
+}
+
+#[test]
+fn quiet_prints_one_line_for_multi_value_commands() {
+    let vectors = temp_path("quiet-verify-vector.json");
+    std::fs::write(
+        &vectors,
+        format!(
+            r#"[{{"type": "ecies", "message": "1", "publicKeyX": "{}", "publicKeyY": "{}", "bidPrivateKey": "3", "salt": "1", "ciphertext": "{}", "bidPublicKeyX": "{}", "bidPublicKeyY": "{}"}}]"#,
+            RECIPIENT_PUBLIC_KEY_X,
+            RECIPIENT_PUBLIC_KEY_Y,
+            CIPHERTEXT,
+            BID_PUBLIC_KEY_X,
+            BID_PUBLIC_KEY_Y
+        ),
+    )
+    .unwrap();
+    let bidder = "0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496";
+    let commands: [&[&str]; 6] = [
+        &["keygen"],
+        &["keygen", "--count", "3"],
+        &["seal-bid", "1", bidder, "1000000000000000000", "1", "2"],
+        &[
+            "decrypt-emp",
+            "0xafe562c841d1023efc69db1db9659b9a5960e3fe10c9d2259736a37504fb4350",
+            BID_PUBLIC_KEY_X,
+            BID_PUBLIC_KEY_Y,
+            "1",
+            "1",
+            bidder,
+            "1000000000000000000",
+        ],
+        &["verify-vector", vectors.to_str().unwrap()],
+        &["version"],
+    ];
+
+    for command in commands {
+        let args = [&["--quiet"], command].concat();
+        let output = ecies_cli(&args);
+        assert_eq!(output.lines().count(), 1, "ecies-cli {:?}", args);
+        serde_json::from_str::<serde_json::Value>(&output).unwrap();
+    }
+    std::fs::remove_file(&vectors).unwrap();
+}
+
+#[test]
+fn estimate_matches_the_encrypted_blob_length() {
+    let encrypted = ecies_cli(&[
+        "encrypt",
+        "1",
+        RECIPIENT_PUBLIC_KEY_X,
+        RECIPIENT_PUBLIC_KEY_Y,
+        "3",
+        "1",
+    ]);
+    let length = (encrypted.len() - 2) / 2;
+
+    assert_eq!(
+        ecies_cli(&["encrypt", "--estimate", "1"]),
+        length.to_string()
+    );
+    let estimate: serde_json::Value = serde_json::from_str(&ecies_cli(&[
+        "encrypt",
+        "--estimate",
+        "--format",
+        "json",
+        "1",
+    ]))
+    .unwrap();
+    assert_eq!(estimate, serde_json::json!({ "byteLength": length }));
+}
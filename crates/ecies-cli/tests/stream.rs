@@ -0,0 +1,89 @@
+// Tests for streaming AES-GCM encryption of files too large to hold in memory
+
+use std::process::Command;
+
+// Helper function to run the CLI and return its trimmed stdout
+fn ecies_cli(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "ecies-cli {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn stream_encrypt_then_decrypt_recovers_multi_megabyte_file() {
+    // The directory is unique to this test process, so concurrent runs from different checkouts cannot collide
+    let dir = std::env::temp_dir().join(format!("ecies-cli-{}-stream", std::process::id()));
+    let (message, ciphertext, decrypted) = (
+        dir.join("message.bin"),
+        dir.join("ciphertext.bin"),
+        dir.join("decrypted.bin"),
+    );
+    std::fs::create_dir_all(&dir).unwrap();
+    // 3 MiB and a partial chunk, with bytes that do not repeat on a chunk boundary
+    let contents: Vec<u8> = (0..3 * 1024 * 1024 + 12345u32)
+        .map(|i| (i % 251) as u8)
+        .collect();
+    std::fs::write(&message, &contents).unwrap();
+
+    // Encrypt to 2G with bid private key 3 and a random salt, then decrypt with private key 2
+    // aes-gcm requires --random-salt, which prints the salt to stderr even with --quiet
+    let args = [
+        "encrypt",
+        "--quiet",
+        "--cipher",
+        "aes-gcm",
+        "--random-salt",
+        "--stream-from",
+        message.to_str().unwrap(),
+        "--stream-to",
+        ciphertext.to_str().unwrap(),
+        "--pubkey-blob",
+        "0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd315ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+        "3",
+    ];
+    let output = Command::new(env!("CARGO_BIN_EXE_ecies-cli"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "ecies-cli {:?} failed", args);
+    let bid_public_key = String::from_utf8(output.stdout).unwrap().trim().to_string();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let salt = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("salt: "))
+        .unwrap();
+    let bid_public_key_x = format!("0x{}", &bid_public_key[2..66]);
+    let bid_public_key_y = format!("0x{}", &bid_public_key[66..]);
+    ecies_cli(&[
+        "decrypt",
+        "--quiet",
+        "--cipher",
+        "aes-gcm",
+        "--stream-from",
+        ciphertext.to_str().unwrap(),
+        "--stream-to",
+        decrypted.to_str().unwrap(),
+        &bid_public_key_x,
+        &bid_public_key_y,
+        "2",
+        salt,
+    ]);
+
+    let (ciphertext, decrypted) = (
+        std::fs::read(&ciphertext).unwrap(),
+        std::fs::read(&decrypted).unwrap(),
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_ne!(ciphertext, contents);
+    assert_eq!(decrypted, contents);
+}
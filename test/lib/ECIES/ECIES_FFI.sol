@@ -108,4 +108,24 @@ abstract contract ECIESFFITest is Test {
         // Decode the output and assign to return variable
         salt_ = abi.decode(output, (uint256));
     }
+
+    function _difftest(uint64 seed_) internal returns (uint256[8] memory fields_) {
+        // Construct the inputs
+        string memory seed = vm.toString(seed_);
+
+        // Create inputs for the FFI
+        // The comma-separated fields of the single vector are joined into one hex string, so the FFI decodes them as bytes
+        string[] memory inputs = new string[](3);
+        inputs[0] = "bash";
+        inputs[1] = "-c";
+        inputs[2] = string.concat(
+            _EXECUTABLE, " difftest 1 --seed ", seed, " 2>/dev/null | sed 's/,0x//g'"
+        );
+
+        // Execute the FFI
+        bytes memory output = vm.ffi(inputs);
+
+        // Decode the output and assign to return variable
+        fields_ = abi.decode(output, (uint256[8]));
+    }
 }
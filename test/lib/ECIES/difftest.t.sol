@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.19;
+
+// Testing Libraries
+import {ECIESFFITest} from "./ECIES_FFI.sol";
+
+// ECIES
+import {Point, ECIES} from "src/lib/ECIES.sol";
+
+contract ECIESDifftestTest is ECIESFFITest {
+    // [X] it encrypts the vector emitted by `ecies-cli difftest` to the same ciphertext and bid public key
+    // [X] it decrypts the emitted ciphertext to the emitted message
+
+    function testFuzz_difftest(uint64 seed_) public {
+        // Generate a vector from the seed using the FFI
+        // Fields: message, public key x, public key y, bid private key, salt, ciphertext, bid public key x, bid public key y
+        uint256[8] memory fields = _difftest(seed_);
+        Point memory publicKey = Point(fields[1], fields[2]);
+
+        // Encrypt the message and compare with the CLI
+        (uint256 ciphertext, Point memory bidPublicKey) =
+            ECIES.encrypt(fields[0], publicKey, fields[3], fields[4]);
+        assertEq(ciphertext, fields[5]);
+        assertEq(bidPublicKey.x, fields[6]);
+        assertEq(bidPublicKey.y, fields[7]);
+
+        // Decrypt the CLI ciphertext
+        // The vector does not include the recipient private key, but the shared secret is the same when
+        // the recipient public key is combined with the bid private key
+        uint256 message = ECIES.decrypt(fields[5], publicKey, fields[3], fields[4]);
+        assertEq(message, fields[0]);
+    }
+}
@@ -24,6 +24,18 @@ contract SaltTest is ECIESFFITest {
         assertEq(expectedSalt, salt);
     }
 
+    function test_saltDerive() public {
+        // Setup salt parameters
+        uint96 lotId = 1;
+        address bidder = address(this);
+        uint96 amount = 1e18;
+
+        // The abi.encode salt, with each field padded to a 32-byte word, as `ecies-cli salt-derive` computes it
+        // The same value is asserted against the CLI in crates/ecies-cli/tests/golden.rs
+        uint256 salt = uint256(keccak256(abi.encode(lotId, bidder, amount)));
+        assertEq(salt, 0xc0ddb7e6af41b34e4d2013fc015b12dd5e77b84776a4d4fac718dcd64bfd8ea8);
+    }
+
     function testFuzz_salt(uint96 lotId_, address bidder_, uint96 amount_) public {
         // Generate the salt locally
         uint256 expectedSalt = uint256(keccak256(abi.encodePacked(lotId_, bidder_, amount_)));